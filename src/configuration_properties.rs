@@ -1,4 +1,4 @@
-use bevy::prelude::Color;
+use bevy::prelude::{Color, Vec2};
 
 pub const CLEAR_COLOR: Color = Color::rgb(0.1, 0.1, 0.1);
 
@@ -8,12 +8,32 @@ pub const WINDOW_HEIGHT: f32 = 800.0;
 
 pub const MAP_TILE_SIZE: f32 = 20.0;
 
+/// World x of the first map column, puts the left map edge at the left window edge
+pub const MAP_ORIGIN_X: f32 = RESOLUTION_RATIO * WINDOW_HEIGHT / -2.0;
+
 pub const PLAYER_MOVE_SPEED: f32 = 5.0;
 
+pub const ENEMY_MOVE_SPEED: f32 = 5.0;
+
+/// Highest ledge a tank climbs on its own, staircase terrain of such steps drives like a slope
+pub const TANK_MAX_STEP_HEIGHT: f32 = 1.0 * MAP_TILE_SIZE;
+
+/// Distance of the tank tracks from its centre, ground is sampled under both to tilt the hull
+pub const TANK_TRACK_OFFSET: f32 = 1.5 * MAP_TILE_SIZE;
+
+/// Steepest hull tilt, degrees
+pub const TANK_MAX_TILT: f32 = 35.0;
+
+/// How fast the hull turns to match the ground, degrees per second
+pub const TANK_TILT_SPEED: f32 = 120.0;
+
 pub const WINDOW_TITLE: &str = "RustiArti | SCR MM";
 
 pub const MAP_LAYOUT_FILE_PATH: &str = "assets/map.mp";
 
+/// Key bindings, written with the defaults on first start
+pub const CONTROLS_CONFIG_FILE_PATH: &str = "controls.ron";
+
 pub const ASCII_SPRITES_FILE_MAP_PATH: &str = "Ascii.png";
 
 pub const EXPLOSION_SPRITES_FILE_MAP_PATH: &str = "explosion_sprites.png";
@@ -49,4 +69,191 @@ pub const Z_PROJECTILE: f32 = 200.0;
 /// Kpep it relative to the map size
 pub const MAP_GRAVITY: f32 = 3.0 * MAP_TILE_SIZE;
 
+/// Fixed physics step in seconds, same for every frame rate
+pub const PHYSICS_TIME_STEP: f32 = 1.0 / 120.0;
+
+/// Upper limit of physics steps simulated in one frame, protects against lag spirals
+pub const PHYSICS_MAX_STEPS_PER_FRAME: u32 = 12;
+
+/// Fraction of horizontal shell speed lost per second
+pub const PROJECTILE_AIR_DRAG: f32 = 0.024;
+
+/// Starting power, before the player charges the first shot
 pub const PROJECTILE_MUZZLE_VELOCITY: f32 = 220.0;
+
+pub const PROJECTILE_MIN_MUZZLE_VELOCITY: f32 = 80.0;
+
+pub const PROJECTILE_MAX_MUZZLE_VELOCITY: f32 = 340.0;
+
+/// Muzzle velocity gained per second while fire is held
+pub const POWER_CHARGE_RATE: f32 = 160.0;
+
+/// Shells still flying after this many seconds are removed without exploding
+pub const PROJECTILE_MAX_FLIGHT_TIME: f32 = 15.0;
+
+pub const TANK_MAX_HEALTH: f32 = 100.0;
+
+/// Area around the tank centre that counts as a direct hit
+pub const TANK_HITBOX_SIZE: Vec2 = Vec2::new(4.0 * MAP_TILE_SIZE, 2.0 * MAP_TILE_SIZE);
+
+pub const PROJECTILE_HITBOX_SIZE: f32 = MAP_TILE_SIZE * 0.5;
+
+/// HE damage at the impact point, falls off to zero at HE_SPLASH_RADIUS
+pub const HE_SPLASH_DAMAGE: f32 = 50.0;
+
+pub const HE_SPLASH_RADIUS: f32 = 4.0 * MAP_TILE_SIZE;
+
+/// AP only damages the tank it hits directly, but much harder than HE
+pub const AP_DIRECT_HIT_DAMAGE: f32 = 80.0;
+
+/// Incidence angle (degrees from the surface normal) above which AP shells ricochet
+pub const AP_RICOCHET_ANGLE: f32 = 70.0;
+
+pub const AP_RICOCHET_SPEED_RETAINED: f32 = 0.6;
+
+pub const AP_MAX_RICOCHETS: u8 = 2;
+
+/// Destroyed tanks get a bigger explosion than shell impacts
+pub const TANK_EXPLOSION_SCALE: f32 = 3.0;
+
+/// Strongest possible wind, horizontal acceleration relative to the map size like gravity
+pub const WIND_MAX_ACCELERATION: f32 = 1.0 * MAP_TILE_SIZE;
+
+/// HUD is a camera child, this puts it in front of the whole scene
+pub const HUD_CAMERA_Z_OFFSET: f32 = -500.0;
+
+pub const HUD_LINE_WIND: usize = 0;
+
+pub const HUD_LINE_POWER: usize = 1;
+
+/// Preview stops after this many physics steps even if nothing was hit
+pub const PREVIEW_MAX_STEPS: usize = 1200;
+
+pub const PREVIEW_STEPS_PER_DOT: usize = 10;
+
+pub const PREVIEW_DOT_COUNT: usize = 60;
+
+/// Terrain tiles closer than this to an HE impact are destroyed
+pub const HE_CRATER_RADIUS: f32 = 2.5 * MAP_TILE_SIZE;
+
+pub const AP_CRATER_RADIUS: f32 = 1.0 * MAP_TILE_SIZE;
+
+pub const HUD_LINE_WEAPON: usize = 2;
+
+pub const CLUSTER_STARTING_AMMO: u32 = 3;
+
+pub const AIRBURST_STARTING_AMMO: u32 = 3;
+
+pub const MIRV_STARTING_AMMO: u32 = 2;
+
+/// Bomblets released by a cluster shell on impact
+pub const CLUSTER_BOMBLETS: usize = 5;
+
+pub const CLUSTER_BOMBLET_SPEED: f32 = 120.0;
+
+/// Bomblets fan out this many degrees to each side of the surface normal
+pub const CLUSTER_SPREAD_ANGLE: f32 = 60.0;
+
+/// Airburst shells explode in the air after this many seconds of flight
+pub const AIRBURST_FUSE_TIME: f32 = 2.0;
+
+/// Warheads released by a MIRV shell at the top of its arc
+pub const MIRV_WARHEADS: usize = 3;
+
+/// Horizontal speed difference between neighbouring MIRV warheads
+pub const MIRV_SPREAD_SPEED: f32 = 40.0;
+
+/// How far past the map sides and below its bottom a projectile or tank is considered lost
+pub const MAP_KILL_ZONE_MARGIN: f32 = 5.0 * MAP_TILE_SIZE;
+
+/// Camera position relative to the tank it rests on
+pub const CAMERA_TANK_OFFSET: Vec2 = Vec2::new(250.0, 150.0);
+
+/// Camera scale while following shells, above 1.0 shows more of the map
+pub const CAMERA_PROJECTILE_ZOOM: f32 = 1.6;
+
+/// Seconds the camera stays on the impact before going back to the tank
+pub const CAMERA_IMPACT_HOLD_TIME: f32 = 1.0;
+
+/// How quickly the camera catches up with its target, higher is snappier
+pub const CAMERA_FOLLOW_SHARPNESS: f32 = 4.0;
+
+/// Seconds between enemy shots
+pub const ENEMY_RELOAD_TIME: f32 = 3.0;
+
+/// Part of the last miss the enemy corrects for on its next shot
+pub const ENEMY_AIM_CORRECTION_RATE: f32 = 0.6;
+
+pub const ENEMY_MAX_AIM_CORRECTION: f32 = 8.0 * MAP_TILE_SIZE;
+
+/// Random spread of enemy shots per difficulty - aim angle in degrees
+pub const ENEMY_ANGLE_ERROR_TRAINING: f32 = 8.0;
+
+pub const ENEMY_ANGLE_ERROR_NORMAL: f32 = 4.0;
+
+pub const ENEMY_ANGLE_ERROR_COMPETITIVE: f32 = 1.5;
+
+/// Random spread of enemy shots per difficulty - muzzle velocity
+pub const ENEMY_POWER_ERROR_TRAINING: f32 = 30.0;
+
+pub const ENEMY_POWER_ERROR_NORMAL: f32 = 15.0;
+
+pub const ENEMY_POWER_ERROR_COMPETITIVE: f32 = 5.0;
+
+pub const HUD_LINE_TURN: usize = 3;
+
+/// Seconds a tank has to take its shot in the turn-based mode
+pub const TURN_TIME: f32 = 20.0;
+
+/// Seconds of driving on a full tank - refilled every turn in the turn-based mode,
+/// on competitive difficulty it has to last the whole match
+pub const FUEL_CAPACITY: f32 = 3.0;
+
+pub const HUD_LINE_WAVE: usize = 4;
+
+/// Waves to clear for a win, survival mode keeps going until the player is destroyed
+pub const WAVE_COUNT: u32 = 3;
+
+/// Extra enemies every next wave brings
+pub const WAVE_SIZE_GROWTH: usize = 1;
+
+/// Seconds of calm between a cleared wave and the next one
+pub const WAVE_BREAK_TIME: f32 = 4.0;
+
+/// Seconds between enemies of the same wave entering the map
+pub const WAVE_SPAWN_INTERVAL: f32 = 1.5;
+
+/// Gap between enemies sharing a spawn point, extras line up on both sides of it
+pub const WAVE_SPAWN_SPACING: f32 = 5.0 * MAP_TILE_SIZE;
+
+pub const HUD_LINE_FUEL: usize = 5;
+
+/// Turret pivot relative to the tank centre
+pub const TURRET_PIVOT_OFFSET: Vec2 = Vec2::new(0.0, 0.5 * MAP_TILE_SIZE);
+
+pub const TURRET_BARREL_LENGTH: f32 = 1.6 * MAP_TILE_SIZE;
+
+/// How far the barrel jumps back when firing
+pub const TURRET_RECOIL_DISTANCE: f32 = 0.4 * MAP_TILE_SIZE;
+
+pub const TURRET_RECOIL_RECOVERY_SPEED: f32 = 1.2 * MAP_TILE_SIZE;
+
+pub const MUZZLE_FLASH_SCALE: f32 = 0.5;
+
+/// Stick deflection ignored as drift, past it a stick also counts as the matching action
+pub const GAMEPAD_STICK_DEADZONE: f32 = 0.2;
+
+/// Gun elevation range of every tank, degrees above the horizon
+pub const TANK_MIN_ELEVATION: f32 = -10.0;
+
+pub const TANK_MAX_ELEVATION: f32 = 90.0;
+
+/// How fast the aim turns with the aim keys or a fully pushed stick, degrees per second
+pub const AIM_SPEED: f32 = 40.0;
+
+/// Part of the aim speed left while fine aim is held
+pub const AIM_FINE_SPEED_FACTOR: f32 = 0.1;
+
+pub const HUD_LINE_ANGLE: usize = 6;
+
+pub const HUD_LINE_DIFFICULTY: usize = 7;
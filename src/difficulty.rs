@@ -0,0 +1,43 @@
+use bevy::prelude::{Reflect, Resource};
+
+use crate::configuration_properties::{
+    ENEMY_ANGLE_ERROR_COMPETITIVE, ENEMY_ANGLE_ERROR_NORMAL, ENEMY_ANGLE_ERROR_TRAINING,
+    ENEMY_POWER_ERROR_COMPETITIVE, ENEMY_POWER_ERROR_NORMAL, ENEMY_POWER_ERROR_TRAINING,
+};
+
+/// Selected difficulty, decides which aiming aids are available
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Resource, Debug, Default, Clone, Eq, PartialEq, Hash, Copy, Reflect)]
+pub enum Difficulty {
+    #[default]
+    TRAINING,
+    NORMAL,
+    COMPETITIVE,
+}
+
+impl Difficulty {
+    /// Next difficulty in the TRAINING, NORMAL, COMPETITIVE cycle
+    pub fn next(&self) -> Self {
+        match self {
+            Difficulty::TRAINING => Difficulty::NORMAL,
+            Difficulty::NORMAL => Difficulty::COMPETITIVE,
+            Difficulty::COMPETITIVE => Difficulty::TRAINING,
+        }
+    }
+
+    /// Dotted arc showing where the shell will fly
+    pub fn trajectory_preview(&self) -> bool {
+        *self == Difficulty::TRAINING
+    }
+
+    /// Largest random error of enemy shots - (aim angle in degrees, muzzle velocity)
+    pub fn enemy_aim_error(&self) -> (f32, f32) {
+        match self {
+            Difficulty::TRAINING => (ENEMY_ANGLE_ERROR_TRAINING, ENEMY_POWER_ERROR_TRAINING),
+            Difficulty::NORMAL => (ENEMY_ANGLE_ERROR_NORMAL, ENEMY_POWER_ERROR_NORMAL),
+            Difficulty::COMPETITIVE => {
+                (ENEMY_ANGLE_ERROR_COMPETITIVE, ENEMY_POWER_ERROR_COMPETITIVE)
+            }
+        }
+    }
+}
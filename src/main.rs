@@ -10,12 +10,22 @@ use crate::camera_util::spawn_camera;
 use crate::config::{RESOLUTION_RATIO, WINDOW_HEIGHT};
 use crate::configuration_properties::WINDOW_TITLE;
 use crate::plugin::ascii_sprite::AsciiSprite;
+use crate::plugin::camera::CameraPlugin;
+use crate::plugin::controls::ControlsPlugin;
 use crate::plugin::debug::DebugPlugin;
+use crate::plugin::difficulty::DifficultyPlugin;
 use crate::plugin::enemy::EnemyPlugin;
 use crate::plugin::explosion::ExplosionPlugin;
+use crate::plugin::health::HealthPlugin;
 use crate::plugin::map::MapPlugin;
+use crate::plugin::physics::PhysicsPlugin;
 use crate::plugin::player::PlayerPlugin;
 use crate::plugin::projectile::ProjectilePlugin;
+use crate::plugin::tank_physics::TankPhysicsPlugin;
+use crate::plugin::trajectory_preview::TrajectoryPreviewPlugin;
+use crate::plugin::turn::TurnPlugin;
+use crate::plugin::wave::WavePlugin;
+use crate::plugin::wind::WindPlugin;
 use crate::state::MainGameState;
 
 mod plugin;
@@ -23,6 +33,7 @@ mod state;
 
 mod camera_util;
 mod configuration_properties;
+mod difficulty;
 mod utilsystems;
 
 fn main() {
@@ -42,11 +53,21 @@ fn main() {
         .add_startup_system(spawn_camera)
         .add_plugin(FrameTimeDiagnosticsPlugin)
         .add_plugin(DebugPlugin)
+        .add_plugin(ControlsPlugin)
+        .add_plugin(DifficultyPlugin)
+        .add_plugin(PhysicsPlugin)
         .add_plugin(MapPlugin)
         .add_plugin(AsciiSprite)
         .add_plugin(PlayerPlugin)
+        .add_plugin(TankPhysicsPlugin)
         .add_plugin(ProjectilePlugin)
         .add_plugin(ExplosionPlugin)
         .add_plugin(EnemyPlugin)
+        .add_plugin(WavePlugin)
+        .add_plugin(HealthPlugin)
+        .add_plugin(WindPlugin)
+        .add_plugin(TrajectoryPreviewPlugin)
+        .add_plugin(CameraPlugin)
+        .add_plugin(TurnPlugin)
         .run();
 }
@@ -0,0 +1,114 @@
+use bevy::app::App;
+use bevy::prelude::{
+    Camera, IntoSystemDescriptor, Plugin, Query, Reflect, Res, ResMut, Resource, SystemSet, Time,
+    Timer, Transform, Vec2, Vec3, With, Without,
+};
+use bevy::time::TimerMode;
+
+use crate::configuration_properties::{
+    CAMERA_FOLLOW_SHARPNESS, CAMERA_IMPACT_HOLD_TIME, CAMERA_PROJECTILE_ZOOM, CAMERA_TANK_OFFSET,
+};
+use crate::plugin::player::Player;
+use crate::plugin::projectile::Projectile;
+use crate::plugin::tank_physics::tank_physics;
+use crate::state::MainGameState;
+
+///Moves the camera between the active tank and shells in flight
+pub struct CameraPlugin;
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Reflect, Default, PartialEq, Eq, Clone, Copy)]
+pub enum CameraMode {
+    /// Resting behind the active tank
+    #[default]
+    TANK,
+    /// Tracking shells in flight
+    PROJECTILE,
+    /// Holding on the spot where the last shell went off
+    IMPACT,
+}
+
+#[derive(Resource, Reflect)]
+pub struct CameraDirector {
+    pub(crate) mode: CameraMode,
+    /// Last point of interest - shell position or the impact spot
+    focus: Vec2,
+    /// How long the camera stays on the impact before going back to the tank
+    hold: Timer,
+}
+
+impl Default for CameraDirector {
+    fn default() -> Self {
+        CameraDirector {
+            mode: CameraMode::TANK,
+            focus: Vec2::ZERO,
+            hold: Timer::from_seconds(CAMERA_IMPACT_HOLD_TIME, TimerMode::Once),
+        }
+    }
+}
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CameraDirector>().add_system_set(
+            SystemSet::on_update(MainGameState::COMBAT).with_system(
+                camera_follow
+                    .after(tank_physics)
+                    .after("Projectile movement handler"),
+            ),
+        );
+    }
+}
+
+type CameraFilter = (With<Camera>, Without<Player>, Without<Projectile>);
+
+/// Pick what the camera looks at and ease towards it. Zoom scales the camera transform,
+/// so HUD text attached to the camera keeps its size on screen
+fn camera_follow(
+    time: Res<Time>,
+    mut director: ResMut<CameraDirector>,
+    player_query: Query<&Transform, With<Player>>,
+    projectile_query: Query<&Transform, With<Projectile>>,
+    mut camera_query: Query<&mut Transform, CameraFilter>,
+) {
+    let (Ok(player_transform), Ok(mut camera_transform)) =
+        (player_query.get_single(), camera_query.get_single_mut())
+    else {
+        return;
+    };
+
+    let shells: Vec<Vec2> = projectile_query
+        .iter()
+        .map(|t| t.translation.truncate())
+        .collect();
+
+    if !shells.is_empty() {
+        //Follow the middle of all shells, cluster bomblets and MIRV warheads included
+        director.mode = CameraMode::PROJECTILE;
+        director.focus = shells.iter().sum::<Vec2>() / shells.len() as f32;
+    } else if director.mode == CameraMode::PROJECTILE {
+        director.mode = CameraMode::IMPACT;
+        director.hold.reset();
+    } else if director.mode == CameraMode::IMPACT {
+        director.hold.tick(time.delta());
+        if director.hold.finished() {
+            director.mode = CameraMode::TANK;
+        }
+    }
+
+    let (target, zoom): (Vec2, f32) = match director.mode {
+        CameraMode::TANK => (
+            player_transform.translation.truncate() + CAMERA_TANK_OFFSET,
+            1.0,
+        ),
+        CameraMode::PROJECTILE | CameraMode::IMPACT => (director.focus, CAMERA_PROJECTILE_ZOOM),
+    };
+
+    //Exponential easing - the same feel whatever the frame rate
+    let blend: f32 = 1.0 - (-CAMERA_FOLLOW_SHARPNESS * time.delta_seconds()).exp();
+    let position: Vec2 = camera_transform.translation.truncate().lerp(target, blend);
+    camera_transform.translation.x = position.x;
+    camera_transform.translation.y = position.y;
+    camera_transform.scale = camera_transform
+        .scale
+        .lerp(Vec3::new(zoom, zoom, 1.0), blend);
+}
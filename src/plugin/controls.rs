@@ -0,0 +1,375 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+
+use bevy::app::App;
+use bevy::input::gamepad::gamepad_connection_system;
+use bevy::input::InputSystem;
+use bevy::prelude::{
+    Axis, CoreStage, GamepadAxis, GamepadAxisType, GamepadButton, GamepadButtonType, Gamepads,
+    Input, IntoSystemDescriptor, KeyCode, Plugin, Reflect, Res, ResMut, Resource,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::configuration_properties::{CONTROLS_CONFIG_FILE_PATH, GAMEPAD_STICK_DEADZONE};
+
+///Translates keys and gamepads into gameplay actions through a rebindable table kept in a config file
+pub struct ControlsPlugin;
+
+/// Everything the player can do. Gameplay systems read these through `Input<InputAction>`
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize, Reflect,
+)]
+pub enum InputAction {
+    MoveLeft,
+    MoveRight,
+    AimUp,
+    AimDown,
+    /// Hold to turn the aim slowly for precise shots
+    FineAim,
+    /// Hold to charge power, release to shoot
+    Fire,
+    CycleWeapon,
+    SwitchMatchMode,
+    SwitchSurvival,
+    SwitchDifficulty,
+}
+
+/// Keys and gamepad buttons bound to every action, any of them triggers it.
+/// Ordered to keep the saved file stable
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+pub struct InputBindings {
+    #[serde(default)]
+    pub(crate) keys: BTreeMap<InputAction, Vec<KeyCode>>,
+    #[serde(default)]
+    pub(crate) buttons: BTreeMap<InputAction, Vec<GamepadButtonType>>,
+}
+
+/// Analog strength of driving and aiming, -1.0 to 1.0.
+/// Sticks and triggers give partial values, keys and buttons always the full one
+#[derive(Resource, Default, Reflect)]
+pub struct ActionAxes {
+    /// Negative drives left
+    pub(crate) drive: f32,
+    /// Negative lowers the barrel
+    pub(crate) aim: f32,
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        InputBindings {
+            keys: BTreeMap::from([
+                (InputAction::MoveLeft, vec![KeyCode::A]),
+                (InputAction::MoveRight, vec![KeyCode::D]),
+                (InputAction::AimUp, vec![KeyCode::W]),
+                (InputAction::AimDown, vec![KeyCode::S]),
+                (InputAction::FineAim, vec![KeyCode::LShift]),
+                (InputAction::Fire, vec![KeyCode::Space]),
+                (InputAction::CycleWeapon, vec![KeyCode::E]),
+                (InputAction::SwitchMatchMode, vec![KeyCode::T]),
+                (InputAction::SwitchSurvival, vec![KeyCode::V]),
+                (InputAction::SwitchDifficulty, vec![KeyCode::G]),
+            ]),
+            buttons: BTreeMap::from([
+                (InputAction::MoveLeft, vec![GamepadButtonType::DPadLeft]),
+                (InputAction::MoveRight, vec![GamepadButtonType::DPadRight]),
+                (InputAction::AimUp, vec![GamepadButtonType::DPadUp]),
+                (InputAction::AimDown, vec![GamepadButtonType::DPadDown]),
+                (InputAction::FineAim, vec![GamepadButtonType::LeftTrigger]),
+                (InputAction::Fire, vec![GamepadButtonType::South]),
+                (
+                    InputAction::CycleWeapon,
+                    vec![GamepadButtonType::RightTrigger],
+                ),
+                (
+                    InputAction::SwitchMatchMode,
+                    vec![GamepadButtonType::Select],
+                ),
+                (InputAction::SwitchSurvival, vec![GamepadButtonType::Start]),
+                (
+                    InputAction::SwitchDifficulty,
+                    vec![GamepadButtonType::North],
+                ),
+            ]),
+        }
+    }
+}
+
+impl InputBindings {
+    /// Reads the table from `path`. Actions missing from the file keep their default keys and buttons
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text: String = fs::read_to_string(path).map_err(|error| error.to_string())?;
+        let loaded: InputBindings = ron::from_str(&text).map_err(|error| error.to_string())?;
+
+        let mut bindings: InputBindings = InputBindings::default();
+        bindings.keys.extend(loaded.keys);
+        bindings.buttons.extend(loaded.buttons);
+        Ok(bindings)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let text: String = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|error| error.to_string())?;
+        fs::write(path, text).map_err(|error| error.to_string())
+    }
+}
+
+impl Plugin for ControlsPlugin {
+    /// Bindings inserted before the plugin are kept, otherwise they come from the config file
+    fn build(&self, app: &mut App) {
+        if !app.world.contains_resource::<InputBindings>() {
+            app.insert_resource(load_bindings());
+        }
+
+        app.init_resource::<Input<InputAction>>()
+            .init_resource::<ActionAxes>()
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                update_input_actions
+                    .after(InputSystem)
+                    .after(gamepad_connection_system),
+            )
+            .add_system(save_changed_bindings);
+    }
+}
+
+/// Falls back to the default table when the file is missing or broken,
+/// a missing file is created so there is something to edit
+fn load_bindings() -> InputBindings {
+    match InputBindings::load(CONTROLS_CONFIG_FILE_PATH) {
+        Ok(bindings) => bindings,
+        Err(error) => {
+            eprintln!("Using default controls, {CONTROLS_CONFIG_FILE_PATH} not loaded: {error}");
+            let bindings: InputBindings = InputBindings::default();
+            if fs::metadata(CONTROLS_CONFIG_FILE_PATH).is_err() {
+                if let Err(error) = bindings.save(CONTROLS_CONFIG_FILE_PATH) {
+                    eprintln!("Could not write {CONTROLS_CONFIG_FILE_PATH}: {error}");
+                }
+            }
+            bindings
+        }
+    }
+}
+
+/// Mirrors keys, buttons and sticks of every connected gamepad into actions and axes.
+/// Left stick drives, right stick or the analog triggers aim
+#[allow(clippy::too_many_arguments)]
+fn update_input_actions(
+    keyboard: Res<Input<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    pad_buttons: Res<Input<GamepadButton>>,
+    pad_sticks: Res<Axis<GamepadAxis>>,
+    pad_triggers: Res<Axis<GamepadButton>>,
+    bindings: Res<InputBindings>,
+    mut actions: ResMut<Input<InputAction>>,
+    mut axes: ResMut<ActionAxes>,
+) {
+    let mut drive: f32 = 0.0;
+    let mut aim: f32 = 0.0;
+    for gamepad in gamepads.iter() {
+        let stick = |axis_type: GamepadAxisType| -> f32 {
+            pad_sticks
+                .get(GamepadAxis::new(gamepad, axis_type))
+                .unwrap_or(0.0)
+        };
+        let trigger = |button_type: GamepadButtonType| -> f32 {
+            pad_triggers
+                .get(GamepadButton::new(gamepad, button_type))
+                .unwrap_or(0.0)
+        };
+
+        drive = strongest(drive, stick(GamepadAxisType::LeftStickX));
+        aim = strongest(aim, stick(GamepadAxisType::RightStickY));
+        aim = strongest(
+            aim,
+            trigger(GamepadButtonType::RightTrigger2) - trigger(GamepadButtonType::LeftTrigger2),
+        );
+    }
+
+    // Keys and buttons only, sticks are added on top
+    let bound_held = |action: InputAction| -> bool {
+        let key_held: bool = bindings
+            .keys
+            .get(&action)
+            .is_some_and(|keys| keys.iter().any(|key| keyboard.pressed(*key)));
+        let button_held: bool = bindings.buttons.get(&action).is_some_and(|buttons| {
+            buttons.iter().any(|button_type| {
+                gamepads
+                    .iter()
+                    .any(|gamepad| pad_buttons.pressed(GamepadButton::new(gamepad, *button_type)))
+            })
+        });
+        key_held || button_held
+    };
+    let digital_axis = |negative: InputAction, positive: InputAction| -> f32 {
+        (bound_held(positive) as i32 - bound_held(negative) as i32) as f32
+    };
+
+    drive = strongest(
+        drive,
+        digital_axis(InputAction::MoveLeft, InputAction::MoveRight),
+    );
+    aim = strongest(aim, digital_axis(InputAction::AimDown, InputAction::AimUp));
+    axes.drive = drive;
+    axes.aim = aim;
+
+    let all_actions: BTreeSet<InputAction> = bindings
+        .keys
+        .keys()
+        .chain(bindings.buttons.keys())
+        .chain(&[
+            InputAction::MoveLeft,
+            InputAction::MoveRight,
+            InputAction::AimUp,
+            InputAction::AimDown,
+        ])
+        .copied()
+        .collect();
+
+    actions.clear();
+    for action in all_actions {
+        let held_now: bool = bound_held(action)
+            || match action {
+                InputAction::MoveLeft => drive < 0.0,
+                InputAction::MoveRight => drive > 0.0,
+                InputAction::AimUp => aim > 0.0,
+                InputAction::AimDown => aim < 0.0,
+                _ => false,
+            };
+        if held_now && !actions.pressed(action) {
+            actions.press(action);
+        } else if !held_now && actions.pressed(action) {
+            actions.release(action);
+        }
+    }
+}
+
+/// Picks the input pushed further, values inside the stick deadzone count as none
+fn strongest(current: f32, candidate: f32) -> f32 {
+    if candidate.abs() > GAMEPAD_STICK_DEADZONE && candidate.abs() > current.abs() {
+        candidate.clamp(-1.0, 1.0)
+    } else {
+        current
+    }
+}
+
+/// Keeps the config file in step with bindings changed while the game runs
+fn save_changed_bindings(bindings: Res<InputBindings>) {
+    if !bindings.is_changed() || bindings.is_added() {
+        return;
+    }
+    if let Err(error) = bindings.save(CONTROLS_CONFIG_FILE_PATH) {
+        eprintln!("Could not write {CONTROLS_CONFIG_FILE_PATH}: {error}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::input::gamepad::{GamepadEventRaw, GamepadEventType, GamepadInfo};
+    use bevy::input::InputPlugin;
+    use bevy::prelude::{Events, Gamepad};
+
+    use super::*;
+
+    const PAD: Gamepad = Gamepad { id: 0 };
+
+    /// Headless app with a connected gamepad. Bindings are inserted up front, so no file is touched
+    fn app_with_gamepad() -> App {
+        let mut app: App = App::new();
+        app.add_plugin(InputPlugin)
+            .insert_resource(InputBindings::default())
+            .add_plugin(ControlsPlugin);
+
+        app.world
+            .resource_mut::<Events<GamepadEventRaw>>()
+            .send(GamepadEventRaw::new(
+                PAD,
+                GamepadEventType::Connected(GamepadInfo {
+                    name: "Test pad".to_string(),
+                }),
+            ));
+        app.update();
+        app
+    }
+
+    fn set_stick(app: &mut App, axis_type: GamepadAxisType, value: f32) {
+        app.world
+            .resource_mut::<Axis<GamepadAxis>>()
+            .set(GamepadAxis::new(PAD, axis_type), value);
+    }
+
+    fn press_button(app: &mut App, button_type: GamepadButtonType, value: f32) {
+        app.world
+            .resource_mut::<Events<GamepadEventRaw>>()
+            .send(GamepadEventRaw::new(
+                PAD,
+                GamepadEventType::ButtonChanged(button_type, value),
+            ));
+    }
+
+    #[test]
+    fn sticks_give_analog_drive_and_aim() {
+        let mut app: App = app_with_gamepad();
+        set_stick(&mut app, GamepadAxisType::LeftStickX, -0.6);
+        set_stick(&mut app, GamepadAxisType::RightStickY, 0.4);
+        app.update();
+
+        let axes = app.world.resource::<ActionAxes>();
+        assert_eq!(axes.drive, -0.6);
+        assert_eq!(axes.aim, 0.4);
+
+        let actions = app.world.resource::<Input<InputAction>>();
+        assert!(actions.pressed(InputAction::MoveLeft));
+        assert!(!actions.pressed(InputAction::MoveRight));
+        assert!(actions.pressed(InputAction::AimUp));
+        assert!(!actions.pressed(InputAction::AimDown));
+    }
+
+    #[test]
+    fn stick_drift_is_ignored() {
+        let mut app: App = app_with_gamepad();
+        set_stick(
+            &mut app,
+            GamepadAxisType::LeftStickX,
+            GAMEPAD_STICK_DEADZONE / 2.0,
+        );
+        app.update();
+
+        assert_eq!(app.world.resource::<ActionAxes>().drive, 0.0);
+        assert!(!app
+            .world
+            .resource::<Input<InputAction>>()
+            .pressed(InputAction::MoveRight));
+    }
+
+    #[test]
+    fn fire_button_presses_and_releases_fire() {
+        let mut app: App = app_with_gamepad();
+        press_button(&mut app, GamepadButtonType::South, 1.0);
+        app.update();
+        assert!(app
+            .world
+            .resource::<Input<InputAction>>()
+            .just_pressed(InputAction::Fire));
+
+        app.update();
+        let actions = app.world.resource::<Input<InputAction>>();
+        assert!(actions.pressed(InputAction::Fire));
+        assert!(!actions.just_pressed(InputAction::Fire));
+
+        press_button(&mut app, GamepadButtonType::South, 0.0);
+        app.update();
+        assert!(app
+            .world
+            .resource::<Input<InputAction>>()
+            .just_released(InputAction::Fire));
+    }
+
+    #[test]
+    fn triggers_aim_when_the_stick_is_idle() {
+        let mut app: App = app_with_gamepad();
+        press_button(&mut app, GamepadButtonType::LeftTrigger2, 0.5);
+        app.update();
+
+        assert_eq!(app.world.resource::<ActionAxes>().aim, -0.5);
+    }
+}
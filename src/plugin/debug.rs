@@ -1,16 +1,33 @@
 use crate::plugin::ascii_sprite::AsciiText;
-use crate::plugin::enemy::Enemy;
+use crate::plugin::camera::CameraDirector;
+use crate::plugin::controls::{ActionAxes, InputAction};
+use crate::plugin::enemy::{Enemy, EnemyAi};
 use crate::plugin::explosion::Explosion;
+use crate::plugin::health::Health;
+use crate::plugin::map::{MapBounds, SpawnPoints};
+use crate::plugin::physics::PhysicsClock;
 use crate::plugin::player::Player;
-use crate::plugin::projectile::{Projectile, ProjectileTexture};
+use crate::plugin::projectile::Projectile;
+use crate::plugin::tank_physics::TankBody;
+use crate::plugin::trajectory_preview::TrajectoryDot;
+use crate::plugin::turn::{MatchMode, Turn};
+use crate::plugin::wave::Waves;
+use crate::plugin::wind::{Wind, WindText};
+use crate::utilsystems::aim_limits::AimLimits;
+use crate::utilsystems::fuel::Fuel;
+use crate::utilsystems::hud_text::HudLine;
 use crate::utilsystems::player_aim::{PlayerAim, PlayerCrosshair};
 use bevy::prelude::{App, Plugin};
 use bevy_inspector_egui::quick::{
     ResourceInspectorPlugin, StateInspectorPlugin, WorldInspectorPlugin,
 };
 
+use crate::difficulty::Difficulty;
 use crate::state::{MainGameState, ProjectilePresent};
 use crate::utilsystems::tank_sprite_resource::{TankEnemyTexture, TankTexture};
+use crate::utilsystems::tile_grid::TileGrid;
+use crate::utilsystems::turret::{Barrel, Turret};
+use crate::utilsystems::weapon_inventory::WeaponInventory;
 
 pub(crate) struct DebugPlugin;
 
@@ -18,17 +35,41 @@ impl Plugin for DebugPlugin {
     fn build(&self, app: &mut App) {
         if cfg!(debug_assertions) {
             app.add_plugin(WorldInspectorPlugin)
+                .register_type::<InputAction>()
+                .register_type::<ActionAxes>()
                 .register_type::<Player>()
+                .register_type::<TankBody>()
+                .register_type::<Fuel>()
+                .register_type::<Turret>()
+                .register_type::<Barrel>()
                 .register_type::<Projectile>()
                 .register_type::<Explosion>()
                 .register_type::<AsciiText>()
+                .register_type::<HudLine>()
                 .register_type::<TankTexture>()
                 .register_type::<PlayerCrosshair>()
                 .register_type::<PlayerAim>()
+                .register_type::<AimLimits>()
                 .register_type::<Enemy>()
+                .register_type::<EnemyAi>()
                 .register_type::<TankEnemyTexture>()
+                .register_type::<Health>()
+                .register_type::<WeaponInventory>()
+                .register_type::<Wind>()
+                .register_type::<WindText>()
+                .register_type::<Difficulty>()
+                .register_type::<PhysicsClock>()
+                .register_type::<TileGrid>()
+                .register_type::<MapBounds>()
+                .register_type::<SpawnPoints>()
+                .register_type::<TrajectoryDot>()
+                .register_type::<CameraDirector>()
+                .register_type::<MatchMode>()
+                .register_type::<Turn>()
+                .register_type::<Waves>()
                 .add_plugin(StateInspectorPlugin::<ProjectilePresent>::default())
-                .add_plugin(StateInspectorPlugin::<MainGameState>::default());
+                .add_plugin(StateInspectorPlugin::<MainGameState>::default())
+                .add_plugin(ResourceInspectorPlugin::<Wind>::default());
             // .add_plugin(ResourceInspectorPlugin::<ProjectileTexture>::default());
         }
     }
@@ -0,0 +1,48 @@
+use bevy::app::App;
+use bevy::prelude::{Camera, Commands, Entity, Input, Plugin, Query, Res, ResMut, With};
+
+use crate::configuration_properties::HUD_LINE_DIFFICULTY;
+use crate::difficulty::Difficulty;
+use crate::plugin::ascii_sprite::AsciiSheet;
+use crate::plugin::controls::InputAction;
+use crate::utilsystems::hud_text::{refresh_hud_line, HudLine};
+
+///Selected difficulty, switched in game and shown in the HUD. Preview, enemy aim and fuel all read it
+pub struct DifficultyPlugin;
+
+impl Plugin for DifficultyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Difficulty>()
+            .add_system(switch_difficulty)
+            .add_system(update_difficulty_text);
+    }
+}
+
+/// Cycle the difficulty, listen on the SwitchDifficulty action (G by default)
+fn switch_difficulty(actions: Res<Input<InputAction>>, mut difficulty: ResMut<Difficulty>) {
+    if actions.just_pressed(InputAction::SwitchDifficulty) {
+        *difficulty = difficulty.next();
+    }
+}
+
+/// Respawn difficulty text when it changes
+fn update_difficulty_text(
+    mut commands: Commands,
+    ascii: Res<AsciiSheet>,
+    difficulty: Res<Difficulty>,
+    hud_query: Query<(Entity, &HudLine)>,
+    camera_query: Query<Entity, With<Camera>>,
+) {
+    let Ok(camera) = camera_query.get_single() else {
+        return;
+    };
+    let text: String = format!("DIFFICULTY {:?}", *difficulty);
+    refresh_hud_line(
+        &mut commands,
+        &ascii,
+        camera,
+        &hud_query,
+        HUD_LINE_DIFFICULTY,
+        Some(text),
+    );
+}
@@ -1,36 +1,205 @@
 use crate::configuration_properties::*;
+use crate::difficulty::Difficulty;
+use crate::plugin::health::Health;
+use crate::plugin::map::SpawnPoints;
+use crate::plugin::player::Player;
+use crate::plugin::projectile::{
+    fire_shell, ProjectileTexture, ProjectileType, ShellImpactEvent, ShotFiredEvent,
+};
+use crate::plugin::tank_physics::TankBody;
+use crate::plugin::turn::{MatchMode, Turn, TurnSide};
+use crate::plugin::wind::Wind;
+use crate::state::{MainGameState, ProjectilePresent};
+use crate::utilsystems::aim_limits::AimLimits;
+use crate::utilsystems::ballistics::produce_initial_speed_vector;
+use crate::utilsystems::enemy_aim::{solve_firing_solution, FiringSolution};
 use crate::utilsystems::enemy_position_provider::generate_random_position;
 use crate::utilsystems::tank_sprite_resource::TankEnemyTexture;
+use crate::utilsystems::tile_grid::TileGrid;
+use crate::utilsystems::turret::{barrel_tip, spawn_turret};
 use bevy::app::App;
 use bevy::prelude::*;
+use bevy::time::TimerMode;
+use rand::Rng;
 
 pub struct EnemyPlugin;
 
 #[derive(Component, Reflect)]
 pub struct Enemy;
 
+///Aims at the player and fires whenever no other shell is in the air
+#[derive(Component, Reflect)]
+pub struct EnemyAi {
+    reload: Timer,
+    /// Horizontal shift of the aim point, learned from where previous shots landed
+    aim_correction: f32,
+    /// Player position the last shot was aimed at, cleared once the shot lands
+    last_target: Option<Vec2>,
+    /// Angle of the last shot, the turret keeps pointing there
+    pub(crate) aim_angle: f32,
+}
+
+impl Default for EnemyAi {
+    fn default() -> Self {
+        EnemyAi {
+            reload: Timer::from_seconds(ENEMY_RELOAD_TIME, TimerMode::Once),
+            aim_correction: 0.0,
+            last_target: None,
+            aim_angle: 90.0,
+        }
+    }
+}
+
 impl Plugin for EnemyPlugin {
     fn build(&self, app: &mut App) {
-        app.add_startup_system(spawn_enemy);
+        app.add_startup_system(spawn_enemy).add_system_set(
+            SystemSet::on_update(MainGameState::COMBAT)
+                .with_system(enemy_fire.after("Player fire"))
+                .with_system(correct_enemy_aim),
+        );
     }
 }
 
-fn spawn_enemy(mut commands: Commands, texture: Res<TankEnemyTexture>) {
-    commands
+/// One enemy on every map spawn point, or a single one at a random spot if the map has none
+fn spawn_enemy(
+    mut commands: Commands,
+    texture: Res<TankEnemyTexture>,
+    spawn_points: Res<SpawnPoints>,
+) {
+    if spawn_points.enemy.is_empty() {
+        let position: Vec2 = Vec2::new(generate_random_position(), -17.9 * MAP_TILE_SIZE);
+        spawn_enemy_tank(&mut commands, &texture, position, "Enemy_1".to_string());
+    }
+    for (index, position) in spawn_points.enemy.iter().enumerate() {
+        spawn_enemy_tank(
+            &mut commands,
+            &texture,
+            *position,
+            format!("Enemy_{}", index + 1),
+        );
+    }
+}
+
+const ENEMY_SPRITE_SCALE: f32 = 0.013;
+
+pub(crate) fn spawn_enemy_tank(
+    commands: &mut Commands,
+    texture: &TankEnemyTexture,
+    position: Vec2,
+    name: String,
+) -> Entity {
+    let enemy = commands
         .spawn(SpriteBundle {
             transform: Transform {
-                scale: Vec3::splat(0.013),
-                translation: Vec3::new(
-                    generate_random_position(),
-                    -17.9 * MAP_TILE_SIZE,
-                    Z_PLAYER + 1.0,
-                ),
+                scale: Vec3::splat(ENEMY_SPRITE_SCALE),
+                translation: position.extend(Z_PLAYER + 1.0),
                 ..default()
             },
             texture: texture.0.clone(),
             visibility: Visibility { is_visible: true },
             ..default()
         })
-        .insert(Name::new("Enemy_1"))
-        .insert(Enemy {});
+        .insert(Name::new(name))
+        .insert(Health::new(TANK_MAX_HEALTH))
+        .insert(TankBody::new(ENEMY_MOVE_SPEED))
+        .insert(EnemyAi::default())
+        .insert(AimLimits::default())
+        .insert(Enemy {})
+        .id();
+
+    spawn_turret(commands, enemy, ENEMY_SPRITE_SCALE);
+    enemy
+}
+
+/// Fire at the player once reloaded and no shell is in flight.
+/// Aim is solved with the shell flight model, then spoiled by a random error depending on difficulty
+#[allow(clippy::too_many_arguments)]
+fn enemy_fire(
+    time: Res<Time>,
+    mut commands: Commands,
+    texture: Res<ProjectileTexture>,
+    difficulty: Res<Difficulty>,
+    match_mode: Res<MatchMode>,
+    turn: Res<Turn>,
+    wind: Res<Wind>,
+    tile_grid: Res<TileGrid>,
+    mut projectile_state: ResMut<State<ProjectilePresent>>,
+    mut shot_events: EventWriter<ShotFiredEvent>,
+    player_query: Query<&Transform, With<Player>>,
+    mut enemy_query: Query<(Entity, &Transform, &AimLimits, &mut EnemyAi), Without<Player>>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    for (enemy, enemy_transform, limits, mut ai) in enemy_query.iter_mut() {
+        if !match_mode.may_fire(&turn, TurnSide::ENEMY) {
+            ai.reload.reset(); //Take the reload time to think once the turn comes
+            continue;
+        }
+        if projectile_state.current() != &ProjectilePresent::NA {
+            continue; //Reload only counts while the sky is clear, so the player gets a window to shoot
+        }
+        ai.reload.tick(time.delta());
+        if !ai.reload.finished() {
+            continue;
+        }
+        if projectile_state.set(ProjectilePresent::PRESENT).is_err() {
+            continue; //Player or another enemy fired this frame already
+        }
+
+        let target: Vec2 = player_transform.translation.truncate();
+        let aim_point: Vec2 = target + Vec2::new(ai.aim_correction, 0.0);
+        let solution: FiringSolution =
+            solve_firing_solution(enemy_transform, aim_point, wind.acceleration, &tile_grid);
+
+        let (angle_error, power_error) = difficulty.enemy_aim_error();
+        let angle: f32 = limits.clamp(solution.angle + random_spread(angle_error));
+        let power: f32 = (solution.power + random_spread(power_error)).clamp(
+            PROJECTILE_MIN_MUZZLE_VELOCITY,
+            PROJECTILE_MAX_MUZZLE_VELOCITY,
+        );
+
+        fire_shell(
+            &mut commands,
+            &texture,
+            &mut shot_events,
+            enemy,
+            barrel_tip(enemy_transform, angle),
+            produce_initial_speed_vector(angle, power),
+            ProjectileType::HE,
+        );
+        ai.last_target = Some(target);
+        ai.aim_angle = angle;
+        ai.reload.reset();
+    }
+}
+
+fn random_spread(max: f32) -> f32 {
+    if max > 0.0 {
+        rand::thread_rng().gen_range(-max..=max)
+    } else {
+        0.0
+    }
+}
+
+/// Shift the next aim point against the horizontal miss of the shot that just landed
+fn correct_enemy_aim(
+    mut impact_events: EventReader<ShellImpactEvent>,
+    mut enemy_query: Query<&mut EnemyAi>,
+) {
+    for impact in impact_events.iter() {
+        let Some(mut ai) = impact
+            .owner
+            .and_then(|owner| enemy_query.get_mut(owner).ok())
+        else {
+            continue;
+        };
+        //Only the first explosion of a shot counts, cluster bomblets land all around
+        let Some(target) = ai.last_target.take() else {
+            continue;
+        };
+        let miss: f32 = impact.position.x - target.x;
+        ai.aim_correction = (ai.aim_correction - miss * ENEMY_AIM_CORRECTION_RATE)
+            .clamp(-ENEMY_MAX_AIM_CORRECTION, ENEMY_MAX_AIM_CORRECTION);
+    }
 }
@@ -80,6 +80,7 @@ fn handle_next_frame(
     }
 }
 
+#[allow(dead_code)]
 fn test_animation(mut commands: Commands, sheet_query: Res<ExplosionTexturesSheet>) {
     spawn_animation(&mut commands, Vec3::new(0.0, -140.0, 200.0), &sheet_query);
 }
@@ -88,6 +89,16 @@ pub fn spawn_animation(
     commands: &mut Commands,
     position: Vec3,
     sheet: &ExplosionTexturesSheet,
+) -> Entity {
+    spawn_scaled_animation(commands, position, sheet, 1.0)
+}
+
+///Same as spawn_animation, but the explosion size is multiplied by `scale`
+pub fn spawn_scaled_animation(
+    commands: &mut Commands,
+    position: Vec3,
+    sheet: &ExplosionTexturesSheet,
+    scale: f32,
 ) -> Entity {
     let sprite: TextureAtlasSprite = TextureAtlasSprite::new(0);
 
@@ -95,7 +106,7 @@ pub fn spawn_animation(
         .spawn(SpriteSheetBundle {
             sprite,
             transform: Transform {
-                scale: Vec3::splat(1.0).mul(MAP_TILE_SIZE / 60.0),
+                scale: Vec3::splat(scale).mul(MAP_TILE_SIZE / 60.0),
                 translation: position,
                 ..default()
             },
@@ -0,0 +1,174 @@
+use bevy::app::App;
+use bevy::prelude::{
+    Commands, Component, DespawnRecursiveExt, Entity, EventReader, EventWriter, Plugin, Query,
+    Reflect, Res, ResMut, State, Transform, Vec3, With,
+};
+
+use crate::configuration_properties::{TANK_EXPLOSION_SCALE, Z_PROJECTILE};
+use crate::plugin::explosion::{spawn_scaled_animation, ExplosionTexturesSheet};
+use crate::plugin::player::Player;
+use crate::state::MainGameState;
+
+///Tracks tank hit points, applies damage and destroys tanks
+pub struct HealthPlugin;
+
+#[derive(Component, Reflect)]
+pub struct Health {
+    pub(crate) hp: f32,
+    pub(crate) max_hp: f32,
+}
+
+/// Request to subtract `amount` hit points from `target`
+pub struct DamageEvent {
+    pub target: Entity,
+    pub amount: f32,
+}
+
+/// Sent once, when tank health drops to zero
+pub struct TankDestroyedEvent {
+    pub tank: Entity,
+}
+
+impl Health {
+    pub fn new(max_hp: f32) -> Self {
+        Health { hp: max_hp, max_hp }
+    }
+
+    /// Subtract damage, never going below zero.
+    /// Returns true only for the hit that destroyed the tank
+    pub fn take_damage(&mut self, amount: f32) -> bool {
+        if self.is_destroyed() {
+            return false;
+        }
+        self.hp = (self.hp - amount.max(0.0)).max(0.0);
+        self.is_destroyed()
+    }
+
+    pub fn is_destroyed(&self) -> bool {
+        self.hp <= 0.0
+    }
+}
+
+impl Plugin for HealthPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DamageEvent>()
+            .add_event::<TankDestroyedEvent>()
+            .add_system(apply_damage)
+            .add_system(destroy_tanks);
+    }
+}
+
+fn apply_damage(
+    mut damage_events: EventReader<DamageEvent>,
+    mut health_query: Query<&mut Health>,
+    mut destroyed_events: EventWriter<TankDestroyedEvent>,
+) {
+    for damage in damage_events.iter() {
+        if let Ok(mut health) = health_query.get_mut(damage.target) {
+            if health.take_damage(damage.amount) {
+                destroyed_events.send(TankDestroyedEvent {
+                    tank: damage.target,
+                });
+            }
+        }
+    }
+}
+
+/// Blow up destroyed tanks. Enemies are removed, losing the player ends the combat
+fn destroy_tanks(
+    mut commands: Commands,
+    mut destroyed_events: EventReader<TankDestroyedEvent>,
+    tank_query: Query<&Transform>,
+    player_query: Query<(), With<Player>>,
+    sheet_query: Res<ExplosionTexturesSheet>,
+    mut game_state: ResMut<State<MainGameState>>,
+) {
+    for destroyed in destroyed_events.iter() {
+        if let Ok(tank_transform) = tank_query.get(destroyed.tank) {
+            spawn_scaled_animation(
+                &mut commands,
+                Vec3::new(
+                    tank_transform.translation.x,
+                    tank_transform.translation.y,
+                    Z_PROJECTILE,
+                ),
+                &sheet_query,
+                TANK_EXPLOSION_SCALE,
+            );
+        }
+
+        if player_query.contains(destroyed.tank) {
+            //Err when the last wave was cleared this frame too and the menu is queued already
+            let _ = game_state.set(MainGameState::MENU);
+        } else {
+            commands.entity(destroyed.tank).despawn_recursive();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::{App, Events};
+
+    use super::*;
+
+    #[test]
+    fn damage_stops_at_zero() {
+        let mut health: Health = Health::new(100.0);
+
+        assert!(!health.take_damage(30.0));
+        assert_eq!(health.hp, 70.0);
+        assert!(health.take_damage(500.0));
+        assert_eq!(health.hp, 0.0);
+    }
+
+    #[test]
+    fn negative_damage_does_not_heal() {
+        let mut health: Health = Health::new(100.0);
+        health.take_damage(40.0);
+
+        assert!(!health.take_damage(-25.0));
+        assert_eq!(health.hp, 60.0);
+    }
+
+    #[test]
+    fn destroyed_tank_takes_no_more_damage() {
+        let mut health: Health = Health::new(100.0);
+        assert!(health.take_damage(100.0));
+
+        assert!(!health.take_damage(10.0));
+        assert!(health.is_destroyed());
+        assert_eq!(health.hp, 0.0);
+    }
+
+    #[test]
+    fn overkill_in_one_frame_sends_a_single_destroyed_event() {
+        let mut app: App = App::new();
+        app.add_event::<DamageEvent>()
+            .add_event::<TankDestroyedEvent>()
+            .add_system(apply_damage);
+        let tank: Entity = app.world.spawn(Health::new(100.0)).id();
+
+        for amount in [60.0, 60.0, 60.0] {
+            app.world.send_event(DamageEvent {
+                target: tank,
+                amount,
+            });
+        }
+        app.update();
+        app.world.send_event(DamageEvent {
+            target: tank,
+            amount: 60.0,
+        });
+        app.update();
+
+        let destroyed = app.world.resource::<Events<TankDestroyedEvent>>();
+        let tanks: Vec<Entity> = destroyed
+            .get_reader()
+            .iter(destroyed)
+            .map(|event| event.tank)
+            .collect();
+        assert_eq!(tanks, vec![tank]);
+        assert_eq!(app.world.get::<Health>(tank).unwrap().hp, 0.0);
+    }
+}
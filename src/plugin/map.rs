@@ -1,18 +1,23 @@
+use std::collections::HashSet;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 
 use bevy::app::App;
 use bevy::prelude::{
-    BuildChildren, Color, Commands, Component, GlobalTransform, Name, Plugin, Query, Res,
-    SpatialBundle, SystemSet, Transform, Vec3, Visibility, With,
+    Added, BuildChildren, Color, Commands, Component, CoreStage, DespawnRecursiveExt, Entity,
+    EventReader, EventWriter, GlobalTransform, Name, Plugin, Query, Reflect, RemovedComponents,
+    Res, ResMut, Resource, SpatialBundle, StartupStage, SystemSet, Transform, Vec2, Vec3,
+    Visibility, With, Without,
 };
 
 use crate::configuration_properties::{
-    MAP_LAYOUT_FILE_PATH, MAP_TILE_SIZE, RESOLUTION_RATIO, WINDOW_HEIGHT, Z_MAP,
+    CLEAR_COLOR, MAP_KILL_ZONE_MARGIN, MAP_LAYOUT_FILE_PATH, MAP_ORIGIN_X, MAP_TILE_SIZE, Z_MAP,
 };
 use crate::plugin::ascii_sprite::{spawn_ascii_sprite, AsciiSheet};
+use crate::plugin::health::{DamageEvent, Health};
 use crate::state::MainGameState;
 use crate::utilsystems::map_textures::{spawn_ground, spawn_mountain};
+use crate::utilsystems::tile_grid::TileGrid;
 
 pub struct MapPlugin;
 
@@ -23,13 +28,83 @@ pub struct Map;
 #[derive(Component)]
 pub struct TileCollider;
 
+type DestructibleFilter = (With<TileCollider>, Without<IndestructibleTile>);
+
+///Map border tiles, explosions cannot destroy them
+#[derive(Component)]
+pub struct IndestructibleTile;
+
+/// Explosion destroying terrain tiles within `radius` from `center`
+pub struct CraterEvent {
+    pub center: Vec2,
+    pub radius: f32,
+}
+
+/// Area covered by the loaded map.mp, measured from the outer edges of the border tiles
+#[derive(Resource, Reflect, Default)]
+pub struct MapBounds {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl MapBounds {
+    /// Past the map sides or below its bottom, further than the kill zone margin.
+    /// There is no ceiling - shells fired high come back down
+    pub fn is_in_kill_zone(&self, point: Vec2) -> bool {
+        point.x < self.min.x - MAP_KILL_ZONE_MARGIN
+            || point.x > self.max.x + MAP_KILL_ZONE_MARGIN
+            || point.y < self.min.y - MAP_KILL_ZONE_MARGIN
+    }
+}
+
+/// Projectile left the playable area without hitting anything
+pub struct ProjectileMissEvent {
+    pub position: Vec2,
+}
+
+/// Tank fell into the kill zone
+pub struct TankFellOffEvent {
+    pub tank: Entity,
+}
+
+/// Lines of map.mp, read once before anything is spawned
+#[derive(Resource)]
+pub struct MapLayout(pub Vec<String>);
+
+/// Tank start positions marked in map.mp
+#[derive(Resource, Reflect, Default)]
+pub struct SpawnPoints {
+    pub player: Vec<Vec2>,
+    pub enemy: Vec<Vec2>,
+}
+
+const PLAYER_SPAWN_SYMBOL: char = 'P';
+
+const ENEMY_SPAWN_SYMBOL: char = 'E';
+
+/// Full block glyph in the ascii sheet, painted over background images where terrain is gone
+const CRATER_GLYPH_INDEX: usize = 219;
+
 impl Plugin for MapPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system_set(SystemSet::on_enter(MainGameState::COMBAT).with_system(show_map))
+        app.add_event::<CraterEvent>()
+            .add_event::<ProjectileMissEvent>()
+            .add_event::<TankFellOffEvent>()
+            .add_system_set(SystemSet::on_enter(MainGameState::COMBAT).with_system(show_map))
             .add_system_set(SystemSet::on_exit(MainGameState::COMBAT).with_system(hide_map))
+            .add_startup_system_to_stage(StartupStage::PreStartup, load_map_layout)
             .add_startup_system(generate_map_outline)
             .add_startup_system(spawn_mountain)
-            .add_startup_system(spawn_ground);
+            .add_startup_system(spawn_ground)
+            .init_resource::<TileGrid>()
+            .init_resource::<MapBounds>()
+            .add_system(carve_craters)
+            .add_system_set(
+                SystemSet::on_update(MainGameState::COMBAT).with_system(remove_fallen_tanks),
+            )
+            .add_system(report_out_of_bounds)
+            .add_system_to_stage(CoreStage::PostUpdate, index_new_tiles)
+            .add_system_to_stage(CoreStage::PostUpdate, unindex_removed_tiles);
     }
 }
 
@@ -48,40 +123,82 @@ fn show_map(map_vis: Query<&mut Visibility, With<Map>>) {
     set_map_visibility(map_vis, true);
 }
 
-fn generate_map_outline(mut commands: Commands, ascii: Res<AsciiSheet>) {
+/// World position of the map cell in given column and line, the same as its tile sprite
+fn map_cell_position(x: usize, y: usize) -> Vec2 {
+    Vec2::new(
+        x as f32 * MAP_TILE_SIZE + MAP_ORIGIN_X,
+        -(y as f32) * MAP_TILE_SIZE,
+    )
+}
+
+/// Read map.mp and collect spawn points, so tanks can be placed during startup
+fn load_map_layout(mut commands: Commands) {
     let file: File = File::open(MAP_LAYOUT_FILE_PATH).expect("No map file found");
+    let lines: Vec<String> = BufReader::new(file).lines().map_while(Result::ok).collect();
+
+    let mut spawn_points: SpawnPoints = SpawnPoints::default();
+    for (y, line) in lines.iter().enumerate() {
+        for (x, char) in line.chars().enumerate() {
+            match char {
+                PLAYER_SPAWN_SYMBOL => spawn_points.player.push(map_cell_position(x, y)),
+                ENEMY_SPAWN_SYMBOL => spawn_points.enemy.push(map_cell_position(x, y)),
+                _ => {}
+            }
+        }
+    }
+
+    commands.insert_resource(spawn_points);
+    commands.insert_resource(MapLayout(lines));
+}
+
+fn generate_map_outline(mut commands: Commands, ascii: Res<AsciiSheet>, layout: Res<MapLayout>) {
+    let lines: &Vec<String> = &layout.0;
     let mut tiles = Vec::new();
 
-    for (y, line) in BufReader::new(file).lines().enumerate() {
-        if let Ok(line) = line {
-            for (x, char) in line.chars().enumerate() {
-                if char == '.' {
-                    continue; //We don't need to render dots. Usable for debug purposes ;)
-                }
-                let map_tile_entity = spawn_ascii_sprite(
-                    &mut commands,
-                    &ascii,
-                    // char as usize,
-                    ' ' as usize,
-                    Color::WHITE,
-                    Vec3::new(
-                        x as f32 * MAP_TILE_SIZE + (RESOLUTION_RATIO * WINDOW_HEIGHT / -2.0),
-                        -(y as f32) * MAP_TILE_SIZE,
-                        Z_MAP,
-                    ),
-                    //Vec3::new(x as f32 * MAP_TILE_SIZE + (RESOLUTION_RATIO*WINDOW_HEIGHT/-2.0) + 30.0, -(y as f32) * MAP_TILE_SIZE - (WINDOW_HEIGHT/-4.0), Z_MAP),
-                    format!("BG_tile{}-{}", char, x),
-                );
-
-                if char == '#' {
-                    commands.entity(map_tile_entity).insert(TileCollider);
-                }
+    for (y, line) in lines.iter().enumerate() {
+        let last_column: usize = line.chars().count().saturating_sub(1);
+        for (x, char) in line.chars().enumerate() {
+            if char == '.' || char == PLAYER_SPAWN_SYMBOL || char == ENEMY_SPAWN_SYMBOL {
+                continue; //We don't need to render dots. Usable for debug purposes ;)
+            }
+            let map_tile_entity = spawn_ascii_sprite(
+                &mut commands,
+                &ascii,
+                // char as usize,
+                ' ' as usize,
+                Color::WHITE,
+                map_cell_position(x, y).extend(Z_MAP),
+                //Vec3::new(x as f32 * MAP_TILE_SIZE + (RESOLUTION_RATIO*WINDOW_HEIGHT/-2.0) + 30.0, -(y as f32) * MAP_TILE_SIZE - (WINDOW_HEIGHT/-4.0), Z_MAP),
+                format!("BG_tile{}-{}", char, x),
+            );
 
-                tiles.push(map_tile_entity);
+            if char == '#' {
+                commands.entity(map_tile_entity).insert(TileCollider);
+                if x == 0 || x == last_column || y + 1 == lines.len() {
+                    commands.entity(map_tile_entity).insert(IndestructibleTile);
+                }
             }
+
+            tiles.push(map_tile_entity);
         }
     }
 
+    let columns: usize = lines
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+    commands.insert_resource(MapBounds {
+        min: Vec2::new(
+            MAP_ORIGIN_X - MAP_TILE_SIZE / 2.0,
+            -(lines.len() as f32 - 0.5) * MAP_TILE_SIZE,
+        ),
+        max: Vec2::new(
+            MAP_ORIGIN_X + (columns as f32 - 0.5) * MAP_TILE_SIZE,
+            MAP_TILE_SIZE / 2.0,
+        ),
+    });
+
     commands
         .spawn(SpatialBundle::default())
         .insert(Map)
@@ -91,3 +208,95 @@ fn generate_map_outline(mut commands: Commands, ascii: Res<AsciiSheet>) {
         .insert(GlobalTransform::default())
         .push_children(&tiles);
 }
+
+/// Remove terrain tiles hit by explosions and paint the holes over the background images
+fn carve_craters(
+    mut commands: Commands,
+    mut crater_events: EventReader<CraterEvent>,
+    tile_query: Query<&Transform, DestructibleFilter>,
+    map_query: Query<Entity, With<Map>>,
+    ascii: Res<AsciiSheet>,
+    tile_grid: Res<TileGrid>,
+) {
+    let mut destroyed: HashSet<Entity> = HashSet::new();
+    for crater in crater_events.iter() {
+        for tile in tile_grid.tiles_within(crater.center, crater.radius) {
+            let Ok(tile_transform) = tile_query.get(tile) else {
+                continue; //Indestructible
+            };
+            if !destroyed.insert(tile) {
+                continue;
+            }
+
+            let hole = spawn_ascii_sprite(
+                &mut commands,
+                &ascii,
+                CRATER_GLYPH_INDEX,
+                CLEAR_COLOR,
+                Vec3::new(
+                    tile_transform.translation.x,
+                    tile_transform.translation.y,
+                    Z_MAP + 2.0,
+                ),
+                "Crater".to_string(),
+            );
+            if let Ok(map) = map_query.get_single() {
+                commands.entity(map).add_child(hole);
+            }
+            commands.entity(tile).despawn_recursive();
+        }
+    }
+}
+
+/// Keep TileGrid in sync with spawned tiles
+fn index_new_tiles(
+    tile_query: Query<(Entity, &Transform), Added<TileCollider>>,
+    mut tile_grid: ResMut<TileGrid>,
+) {
+    for (tile, tile_transform) in tile_query.iter() {
+        tile_grid.insert(tile, tile_transform.translation.truncate());
+    }
+}
+
+/// Keep TileGrid in sync with destroyed tiles. Runs in PostUpdate to see removals made during Update
+fn unindex_removed_tiles(
+    removed_tiles: RemovedComponents<TileCollider>,
+    mut tile_grid: ResMut<TileGrid>,
+) {
+    for tile in removed_tiles.iter() {
+        tile_grid.remove(tile);
+    }
+}
+
+/// Tanks that dropped into the kill zone are lost, whatever health they had left
+fn remove_fallen_tanks(
+    map_bounds: Res<MapBounds>,
+    tank_query: Query<(Entity, &Transform, &Health)>,
+    mut fell_off_events: EventWriter<TankFellOffEvent>,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    for (tank, tank_transform, health) in tank_query.iter() {
+        if health.is_destroyed()
+            || !map_bounds.is_in_kill_zone(tank_transform.translation.truncate())
+        {
+            continue;
+        }
+        fell_off_events.send(TankFellOffEvent { tank });
+        damage_events.send(DamageEvent {
+            target: tank,
+            amount: health.hp,
+        });
+    }
+}
+
+fn report_out_of_bounds(
+    mut miss_events: EventReader<ProjectileMissEvent>,
+    mut fell_off_events: EventReader<TankFellOffEvent>,
+) {
+    for miss in miss_events.iter() {
+        eprintln!("Miss - projectile left the map at {}", miss.position);
+    }
+    for fell_off in fell_off_events.iter() {
+        eprintln!("Tank {:?} fell off the map", fell_off.tank);
+    }
+}
@@ -1,7 +1,17 @@
 pub mod ascii_sprite;
+pub mod camera;
+pub mod controls;
 pub mod debug;
+pub mod difficulty;
 pub mod enemy;
 pub mod explosion;
+pub mod health;
 pub mod map;
+pub mod physics;
 pub mod player;
 pub mod projectile;
+pub mod tank_physics;
+pub mod trajectory_preview;
+pub mod turn;
+pub mod wave;
+pub mod wind;
@@ -0,0 +1,122 @@
+use bevy::app::App;
+use bevy::prelude::{CoreStage, Plugin, Reflect, Res, ResMut, Resource, Time};
+
+use crate::configuration_properties::{PHYSICS_MAX_STEPS_PER_FRAME, PHYSICS_TIME_STEP};
+
+///Splits frame time into fixed physics steps, so shots and tank movement do not depend on FPS
+pub struct PhysicsPlugin;
+
+/// How many fixed steps physics systems have to simulate during the current frame
+#[derive(Resource, Default, Reflect)]
+pub struct PhysicsClock {
+    /// Frame time not yet consumed by a full step
+    accumulator: f64,
+    pub(crate) steps: u32,
+}
+
+impl PhysicsClock {
+    /// Add frame time and work out how many whole steps fit in it.
+    /// Leftover carries over to the next frame. If the game lags behind by more than
+    /// PHYSICS_MAX_STEPS_PER_FRAME steps, the rest is dropped instead of piling up
+    pub fn advance(&mut self, delta_seconds: f64) {
+        let step: f64 = PHYSICS_TIME_STEP as f64;
+        self.accumulator += delta_seconds;
+
+        let available_steps: f64 = (self.accumulator / step).floor();
+        self.accumulator -= available_steps * step;
+        self.steps = (available_steps as u32).min(PHYSICS_MAX_STEPS_PER_FRAME);
+    }
+}
+
+impl Plugin for PhysicsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PhysicsClock>()
+            .add_system_to_stage(CoreStage::PreUpdate, advance_physics_clock);
+    }
+}
+
+fn advance_physics_clock(time: Res<Time>, mut physics_clock: ResMut<PhysicsClock>) {
+    physics_clock.advance(time.delta_seconds_f64());
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::{Entity, IVec2, Vec2};
+
+    use super::PhysicsClock;
+    use crate::configuration_properties::{PHYSICS_MAX_STEPS_PER_FRAME, PHYSICS_TIME_STEP};
+    use crate::utilsystems::ballistics::{flight_step, produce_initial_speed_vector};
+    use crate::utilsystems::tile_grid::TileGrid;
+
+    /// Flat ground, top row of tiles at y = -18 cells
+    fn flat_ground() -> TileGrid {
+        let mut grid: TileGrid = TileGrid::default();
+        for x in 0..120 {
+            for y in -22..=-18 {
+                let tile: Entity = Entity::from_raw((x * 10 - y) as u32);
+                grid.insert(tile, TileGrid::cell_to_world(IVec2::new(x, y)));
+            }
+        }
+        grid
+    }
+
+    /// Fly a shell the way movement_handler does at given frame time.
+    /// Returns physics steps taken and the cell the shell landed in
+    fn fly_shell(frame_time: f64) -> (u32, IVec2) {
+        let grid: TileGrid = flat_ground();
+        let mut clock: PhysicsClock = PhysicsClock::default();
+        let mut position: Vec2 = TileGrid::cell_to_world(IVec2::new(20, -17));
+        let mut velocity: Vec2 = produce_initial_speed_vector(50.0, 220.0);
+        let mut total_steps: u32 = 0;
+
+        for _ in 0..10_000 {
+            clock.advance(frame_time);
+            for _ in 0..clock.steps {
+                total_steps += 1;
+                let start: Vec2 = position;
+                (position, velocity) = flight_step(position, velocity, 4.0, PHYSICS_TIME_STEP);
+                if let Some(hit) = grid.sweep(start, position) {
+                    return (total_steps, TileGrid::world_to_cell(hit.point));
+                }
+            }
+        }
+        panic!("Shell never landed");
+    }
+
+    #[test]
+    fn same_steps_for_same_time_at_any_frame_rate() {
+        let mut clock_60: PhysicsClock = PhysicsClock::default();
+        let mut clock_144: PhysicsClock = PhysicsClock::default();
+        let (mut steps_60, mut steps_144) = (0, 0);
+
+        // Three seconds of frames at each rate
+        for _ in 0..180 {
+            clock_60.advance(1.0 / 60.0);
+            steps_60 += clock_60.steps;
+        }
+        for _ in 0..432 {
+            clock_144.advance(1.0 / 144.0);
+            steps_144 += clock_144.steps;
+        }
+
+        assert_eq!(steps_60, steps_144);
+        assert_eq!(steps_60, (3.0 / PHYSICS_TIME_STEP as f64).floor() as u32);
+    }
+
+    #[test]
+    fn shell_lands_on_the_same_tile_at_60_and_144_fps() {
+        let (steps_60, cell_60) = fly_shell(1.0 / 60.0);
+        let (steps_144, cell_144) = fly_shell(1.0 / 144.0);
+
+        assert_eq!(steps_60, steps_144);
+        assert_eq!(cell_60, cell_144);
+        assert!(cell_60.x > 20, "Shell should land away from the muzzle");
+    }
+
+    #[test]
+    fn lag_spike_is_capped() {
+        let mut clock: PhysicsClock = PhysicsClock::default();
+        clock.advance(5.0);
+        assert_eq!(clock.steps, PHYSICS_MAX_STEPS_PER_FRAME);
+    }
+}
@@ -1,30 +1,39 @@
 use bevy::prelude::{
-    default, App, BuildChildren, Camera, Children, Color, Commands, Component, Input,
-    IntoSystemDescriptor, KeyCode, Name, Plugin, Query, Reflect, Res, SpriteBundle, StartupStage,
-    SystemSet, TextureAtlasSprite, Time, Transform, Vec2, Vec3, Visibility, With, Without,
+    default, App, BuildChildren, Children, Color, Commands, Component, IntoSystemDescriptor, Name,
+    Plugin, Query, Reflect, Res, SpriteBundle, StartupStage, SystemSet, Transform, Vec2, Vec3,
+    Visibility, With, Without,
 };
+use rand::seq::SliceRandom;
 
-use crate::configuration_properties::{MAP_GRAVITY, MAP_TILE_SIZE, PLAYER_MOVE_SPEED, Z_PLAYER};
+use crate::configuration_properties::{
+    MAP_TILE_SIZE, PLAYER_MOVE_SPEED, TANK_MAX_HEALTH, Z_PLAYER,
+};
+use crate::difficulty::Difficulty;
 use crate::plugin::ascii_sprite::{spawn_ascii_sprite, spawn_ascii_text, AsciiSheet};
-use crate::plugin::map::TileCollider;
+use crate::plugin::controls::ActionAxes;
+use crate::plugin::health::Health;
+use crate::plugin::map::{SpawnPoints, TileCollider};
+use crate::plugin::tank_physics::{tank_physics, tilt_tanks, TankBody};
+use crate::plugin::turn::{MatchMode, Turn, TurnSide};
 use crate::state::MainGameState;
+use crate::utilsystems::aim_limits::AimLimits;
+use crate::utilsystems::fuel::{
+    burn_fuel, fuel_limited, refuel_on_new_turn, update_fuel_text, Fuel,
+};
 use crate::utilsystems::player_aim::{
-    spawn_aim_crosshair, update_aim_position, PlayerAim, PlayerCrosshair, CH_RADIUS,
+    charge_power, spawn_aim_crosshair, update_aim_position, update_angle_text, update_power_meter,
+    PlayerAim, PlayerCrosshair, CH_RADIUS,
 };
 use crate::utilsystems::tank_sprite_resource::{
     load_enemy_tank_textures, load_tank_textures, TankTexture,
 };
-use crate::utilsystems::wall_collision::wall_collision_check;
+use crate::utilsystems::turret::{aim_turret, react_to_shots, recover_recoil, spawn_turret};
+use crate::utilsystems::weapon_inventory::WeaponInventory;
 
 pub struct PlayerPlugin;
 
 #[derive(Component, Reflect)]
-pub struct Player {
-    pub(crate) speed: f32,
-    just_moved: bool,
-    ground_contact: bool,
-    vertical_velocity: f32,
-}
+pub struct Player;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
@@ -32,9 +41,18 @@ impl Plugin for PlayerPlugin {
             .add_system_set(SystemSet::on_enter(MainGameState::COMBAT).with_system(show_player))
             .add_system_set(
                 SystemSet::on_update(MainGameState::COMBAT)
-                    .with_system(camera_follow.after(player_movement))
-                    .with_system(player_movement)
-                    .with_system(update_aim_position),
+                    .with_system(player_drive.before(tank_physics))
+                    .with_system(burn_fuel.after(tank_physics))
+                    .with_system(refuel_on_new_turn.before(player_drive))
+                    .with_system(update_fuel_text.after(burn_fuel))
+                    .with_system(move_crosshair.after(tank_physics))
+                    .with_system(update_aim_position)
+                    .with_system(update_angle_text.after(update_aim_position))
+                    .with_system(aim_turret.after(update_aim_position).after(tilt_tanks))
+                    .with_system(react_to_shots)
+                    .with_system(recover_recoil.after(react_to_shots))
+                    .with_system(charge_power)
+                    .with_system(update_power_meter.after(charge_power)),
             )
             .add_startup_system_to_stage(StartupStage::PreStartup, load_tank_textures)
             .add_startup_system_to_stage(StartupStage::PreStartup, load_enemy_tank_textures)
@@ -83,83 +101,58 @@ type AimFilter = (
     Without<TileCollider>,
 );
 
-/// Handle player movement. moving up and down is disabled. Simple gravity is simulated
-fn player_movement(
-    mut player_query: Query<(&mut Player, &PlayerAim, &mut Transform)>,
-    wall_query: Query<&Transform, (With<TileCollider>, Without<Player>)>,
-    mut aim_transform_query: Query<(&mut Transform), AimFilter>,
-    keyboard: Res<Input<KeyCode>>,
-    time: Res<Time>,
+/// Handle player movement input, the tank itself is moved by tank_physics.
+/// Driving locks once the turn is over or the fuel runs out
+fn player_drive(
+    mut player_query: Query<(&mut TankBody, &Fuel), With<Player>>,
+    axes: Res<ActionAxes>,
+    match_mode: Res<MatchMode>,
+    difficulty: Res<Difficulty>,
+    turn: Res<Turn>,
 ) {
-    let (mut player, player_aim, mut transform) = player_query.single_mut();
-    let mut player_ch = aim_transform_query.single_mut();
-    player.just_moved = false;
-
-    player.vertical_velocity += -MAP_GRAVITY * time.delta_seconds();
-
-    let y_delta: f32 = player.vertical_velocity * time.delta_seconds();
+    let (mut body, fuel) = player_query.single_mut();
 
-    let mut x_delta = 0.0;
-    if keyboard.pressed(KeyCode::A) {
-        x_delta -= player.speed * MAP_TILE_SIZE * time.delta_seconds()
-    }
-    if keyboard.pressed(KeyCode::D) {
-        x_delta += player.speed * MAP_TILE_SIZE * time.delta_seconds()
-    }
-
-    let target = transform.translation + Vec3::new(x_delta, 0.0, 0.0);
-    if !wall_query
-        .iter()
-        .any(|t| wall_collision_check(t.translation, target))
-    {
-        if x_delta != 0.0 {
-            player.just_moved = true;
-        }
-        player_ch.translation = target;
-        player_ch.translation.x += CH_RADIUS * player_aim.angle.to_radians().cos();
-        player_ch.translation.y += CH_RADIUS * player_aim.angle.to_radians().sin();
+    let mut direction: f32 = axes.drive;
 
-        transform.translation = target;
-    }
-
-    let target = transform.translation + Vec3::new(0.0, y_delta, 0.0);
-    if !wall_query
-        .iter()
-        .any(|t| wall_collision_check(t.translation, target))
-    {
-        if y_delta != 0.0 {
-            player.just_moved = true;
-        }
-        player.ground_contact = false;
-
-        player_ch.translation = target;
-        player_ch.translation.x += CH_RADIUS * player_aim.angle.to_radians().cos();
-        player_ch.translation.y += CH_RADIUS * player_aim.angle.to_radians().sin();
-
-        transform.translation = target;
-    } else {
-        player.ground_contact = true;
-        player.vertical_velocity = 0.0;
+    let out_of_fuel: bool = fuel_limited(&match_mode, &difficulty) && fuel.is_empty();
+    if out_of_fuel || !match_mode.may_move(&turn, TurnSide::PLAYER) {
+        direction = 0.0;
     }
+    body.drive = direction;
 }
 
-fn camera_follow(
-    player_query: Query<&Transform, With<Player>>,
-    mut camera_query: Query<&mut Transform, (Without<Player>, With<Camera>)>,
+/// Keep the crosshair around the tank, pointing along the aim
+fn move_crosshair(
+    player_query: Query<(&PlayerAim, &Transform), With<Player>>,
+    mut aim_transform_query: Query<&mut Transform, AimFilter>,
 ) {
-    let player_transform = player_query.single();
-    let mut camera_transform = camera_query.single_mut();
+    let (player_aim, transform) = player_query.single();
+    let mut player_ch = aim_transform_query.single_mut();
 
-    camera_transform.translation.x = player_transform.translation.x + 250.0;
-    camera_transform.translation.y = player_transform.translation.y + 150.0;
+    player_ch.translation = transform.translation;
+    player_ch.translation.x += CH_RADIUS * player_aim.angle.to_radians().cos();
+    player_ch.translation.y += CH_RADIUS * player_aim.angle.to_radians().sin();
 }
 
-fn spawn_tank_player(mut commands: Commands, texture: Res<TankTexture>) {
-    commands
+const TANK_SPRITE_SCALE: f32 = 0.15;
+
+/// Spawn the player on one of the map spawn points, picked at random if there are several
+fn spawn_tank_player(
+    mut commands: Commands,
+    texture: Res<TankTexture>,
+    spawn_points: Res<SpawnPoints>,
+) {
+    let position: Vec2 = spawn_points
+        .player
+        .choose(&mut rand::thread_rng())
+        .copied()
+        .unwrap_or(Vec2::new(-400.0, -17.9 * MAP_TILE_SIZE)); //Map without a spawn point
+
+    let player = commands
         .spawn(SpriteBundle {
             transform: Transform {
-                scale: Vec3::splat(0.15),
-                translation: Vec3::new(-400.0, -17.9 * MAP_TILE_SIZE, Z_PLAYER + 1.0),
+                scale: Vec3::splat(TANK_SPRITE_SCALE),
+                translation: position.extend(Z_PLAYER + 1.0),
                 ..default()
             },
             texture: texture.0.clone(),
@@ -167,20 +160,21 @@ fn spawn_tank_player(mut commands: Commands, texture: Res<TankTexture>) {
             ..default()
         })
         .insert(Name::new("Player_1"))
-        .insert(PlayerAim { angle: 5.0 })
-        .insert(Player {
-            speed: PLAYER_MOVE_SPEED,
-            just_moved: false,
-            ground_contact: false,
-            vertical_velocity: 10.0,
-        });
+        .insert(PlayerAim::default())
+        .insert(AimLimits::default())
+        .insert(WeaponInventory::default())
+        .insert(Health::new(TANK_MAX_HEALTH))
+        .insert(TankBody::new(PLAYER_MOVE_SPEED))
+        .insert(Fuel::default())
+        .insert(Player)
+        .id();
+
+    spawn_turret(&mut commands, player, TANK_SPRITE_SCALE);
 }
 
+///Ascii version of the player, kept for debugging without tank textures
+#[allow(dead_code)]
 fn spawn_player(mut commands: Commands, ascii: Res<AsciiSheet>) {
-    let mut sprite: TextureAtlasSprite = TextureAtlasSprite::new(13);
-    sprite.color = Color::rgb(0.3, 0.3, 0.9);
-    sprite.custom_size = Some(Vec2::splat(MAP_TILE_SIZE));
-
     let player_entity = spawn_ascii_sprite(
         &mut commands,
         &ascii,
@@ -194,13 +188,10 @@ fn spawn_player(mut commands: Commands, ascii: Res<AsciiSheet>) {
 
     let _ = commands
         .entity(player_entity)
-        .insert(Player {
-            speed: PLAYER_MOVE_SPEED,
-            just_moved: false,
-            ground_contact: false,
-            vertical_velocity: 10.0,
-        })
-        .insert(PlayerAim { angle: 5.0 })
+        .insert(TankBody::new(PLAYER_MOVE_SPEED))
+        .insert(Player)
+        .insert(PlayerAim::default())
+        .insert(AimLimits::default())
         .add_child(text_ent)
         .id();
 
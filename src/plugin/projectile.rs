@@ -1,25 +1,42 @@
 use std::f32::consts::FRAC_PI_2;
 use std::ops::Mul;
+use std::time::Duration;
 
 use bevy::app::App;
 use bevy::ecs::schedule::ShouldRun;
 use bevy::math::{Vec2, Vec3};
 use bevy::prelude::{
-    default, AssetServer, Commands, Component, DespawnRecursiveExt, Entity, Handle, Image, Input,
-    KeyCode, Mut, Name, Plugin, Quat, Query, Reflect, Res, ResMut, Resource, SpriteBundle,
-    StartupStage, State, SystemSet, Time, Transform, Visibility, With, Without,
+    default, AssetServer, Commands, Component, DespawnRecursiveExt, Entity, EventWriter,
+    FromReflect, Handle, Image, Input, IntoSystemDescriptor, Mut, Name, Plugin, Quat, Query,
+    Reflect, Res, ResMut, Resource, SpriteBundle, StartupStage, State, SystemSet, Timer, Transform,
+    Visibility, With, Without,
 };
+use bevy::time::TimerMode;
 
 use crate::configuration_properties::{
-    MAP_GRAVITY, MAP_TILE_SIZE, PROJECTILE_MUZZLE_VELOCITY, SPRITE_PROJECTILE_TEXTURE_FILE,
+    AIRBURST_FUSE_TIME, AP_CRATER_RADIUS, AP_DIRECT_HIT_DAMAGE, AP_MAX_RICOCHETS, HE_CRATER_RADIUS,
+    MAP_TILE_SIZE, PHYSICS_TIME_STEP, PROJECTILE_MAX_FLIGHT_TIME, SPRITE_PROJECTILE_TEXTURE_FILE,
     Z_PROJECTILE,
 };
+use crate::plugin::controls::InputAction;
 use crate::plugin::explosion::{spawn_animation, ExplosionTexturesSheet};
-use crate::plugin::map::TileCollider;
+use crate::plugin::health::{DamageEvent, Health};
+use crate::plugin::map::{CraterEvent, MapBounds, ProjectileMissEvent};
+use crate::plugin::physics::PhysicsClock;
 use crate::plugin::player::Player;
+use crate::plugin::turn::{MatchMode, Turn, TurnSide};
+use crate::plugin::wind::Wind;
 use crate::state::{MainGameState, ProjectilePresent};
+use crate::utilsystems::ballistics::{flight_step, produce_initial_speed_vector};
 use crate::utilsystems::player_aim::PlayerAim;
-use crate::utilsystems::wall_collision::wall_collision_check;
+use crate::utilsystems::shell_effects::{
+    cluster_bomblet_velocities, distance_to_tank_hitbox, impact_normal, is_ricochet,
+    mirv_warhead_velocities, ricochet_velocity, splash_damage,
+};
+use crate::utilsystems::tile_grid::TileGrid;
+use crate::utilsystems::turret::barrel_tip;
+use crate::utilsystems::wall_collision::{tank_hit_check, tank_sweep_check, SweptHit};
+use crate::utilsystems::weapon_inventory::{cycle_weapon, update_weapon_text, WeaponInventory};
 
 pub struct ProjectilePlugin;
 
@@ -27,25 +44,56 @@ pub struct ProjectilePlugin;
 #[derive(Resource, Reflect)]
 pub struct ProjectileTexture(pub Handle<Image>);
 
-#[derive(Reflect)]
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Reflect, FromReflect, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ProjectileType {
     HE,
     AP,
+    /// Scatters HE bomblets from the impact point
+    CLUSTER,
+    /// Explodes in the air once its fuse runs out, leaves no crater
+    AIRBURST,
+    /// Splits into HE warheads at the top of its arc
+    MIRV,
 }
 
 #[derive(Component, Reflect)]
 pub struct Projectile {
     speed_vector: Vec2,
     variant: ProjectileType,
+    /// Flight time left before the shell is removed without exploding
+    lifetime: Timer,
+    /// Tank that fired the shell - ignored until the shell leaves its hitbox
+    shooter: Option<Entity>,
+    /// Tank the shell, or the shell that released it, was fired from
+    owner: Option<Entity>,
+    /// How many times an AP shell has already bounced off
+    ricochets: u8,
+}
+
+/// Shell went off - on a surface, a tank or in the air
+pub struct ShellImpactEvent {
+    pub owner: Option<Entity>,
+    pub position: Vec2,
+}
+
+/// Tank fired a shell out of `muzzle`
+pub struct ShotFiredEvent {
+    pub tank: Entity,
+    pub muzzle: Vec2,
 }
 
 impl Plugin for ProjectilePlugin {
     fn build(&self, app: &mut App) {
         app.add_startup_system_to_stage(StartupStage::PreStartup, load_texture_asset)
             .add_state(ProjectilePresent::NA)
+            .add_event::<ShellImpactEvent>()
+            .add_event::<ShotFiredEvent>()
             .add_system_set(
                 SystemSet::on_update(MainGameState::COMBAT) // Activate below systems only for combat state
-                    .with_system(fire),
+                    .with_system(fire.label("Player fire"))
+                    .with_system(cycle_weapon)
+                    .with_system(update_weapon_text),
             )
             .add_system_set(
                 SystemSet::new()
@@ -76,61 +124,210 @@ fn load_texture_asset(mut commands: Commands, asset_server: Res<AssetServer>) {
     commands.insert_resource(ProjectileTexture(p_texture));
 }
 
+type TankFilter = (With<Health>, Without<Projectile>);
+
+/// Ricocheting shell is put back this far from the surface, so it does not hit it again at once
+const RICOCHET_SURFACE_GAP: f32 = 0.01;
+
+/// Cluster bomblets are released this far from the hit surface
+const CLUSTER_RELEASE_OFFSET: f32 = 0.6 * MAP_TILE_SIZE;
+
+#[allow(clippy::too_many_arguments)]
 fn movement_handler(
     mut projectile_query: Query<(Entity, &mut Projectile, &mut Transform)>,
-    time: Res<Time>,
-    commands: Commands,
+    physics_clock: Res<PhysicsClock>,
+    mut commands: Commands,
     sheet_query: Res<ExplosionTexturesSheet>,
-    wall_query: Query<&Transform, (With<TileCollider>, Without<Projectile>)>,
+    texture: Res<ProjectileTexture>,
+    tile_grid: Res<TileGrid>,
+    map_bounds: Res<MapBounds>,
+    tank_query: Query<(Entity, &Transform), TankFilter>,
+    mut projectile_state_query: ResMut<State<ProjectilePresent>>,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut crater_events: EventWriter<CraterEvent>,
+    mut miss_events: EventWriter<ProjectileMissEvent>,
+    mut impact_events: EventWriter<ShellImpactEvent>,
+    wind: Res<Wind>,
 ) {
-    if projectile_query.is_empty() {
-        return;
-    }
-    let (entity, mut projectile, mut p_transform) = projectile_query.single_mut();
+    let live_projectiles: usize = projectile_query.iter().count();
+    let mut removed_projectiles: Vec<Entity> = Vec::new();
+    let mut spawned_submunitions: usize = 0;
+    let step_duration: Duration = Duration::from_secs_f32(PHYSICS_TIME_STEP);
+
+    for _ in 0..physics_clock.steps {
+        for (entity, mut projectile, mut p_transform) in projectile_query.iter_mut() {
+            if removed_projectiles.contains(&entity) {
+                continue; //Despawn is deferred, skip shells already gone this frame
+            }
 
-    //Calculate new vertical speed
-    projectile.speed_vector.y += -MAP_GRAVITY * time.delta_seconds(); // acceleration * time = speed
-    projectile.speed_vector.x -= projectile.speed_vector.x * 0.0004; // Very simple air resistance approx. :)
+            projectile.lifetime.tick(step_duration);
+            if projectile.lifetime.finished() {
+                //Flew for too long without hitting anything - remove without explosion
+                commands.entity(entity).despawn_recursive();
+                miss_events.send(ProjectileMissEvent {
+                    position: p_transform.translation.truncate(),
+                });
+                removed_projectiles.push(entity);
+                continue;
+            }
 
-    let delta_x: f32 = projectile.speed_vector.x * time.delta_seconds();
-    let delta_y: f32 = projectile.speed_vector.y * time.delta_seconds(); // speed * time = distance
+            let start: Vec2 = p_transform.translation.truncate();
+            if projectile.variant == ProjectileType::AIRBURST
+                && projectile.lifetime.elapsed_secs() >= AIRBURST_FUSE_TIME
+            {
+                //Fuse ran out - burst in the air, nothing to dig a crater in
+                send_splash_damage(start, &tank_query, &mut damage_events);
+                impact_events.send(ShellImpactEvent {
+                    owner: projectile.owner,
+                    position: start,
+                });
+                stop_movement(&mut projectile);
+                explode_remove(&mut commands, entity, start, &sheet_query);
+                removed_projectiles.push(entity);
+                continue;
+            }
 
-    //Check for collision with walls or ground or player
-    let tmp_transform_target: Vec3 = p_transform.translation + Vec3::new(delta_x, delta_y, 0.0);
-    if !wall_query
-        .iter()
-        .any(|t| wall_collision_check(t.translation, tmp_transform_target))
+            let previous_velocity: Vec2 = projectile.speed_vector;
+            let (target, velocity): (Vec2, Vec2) = flight_step(
+                start,
+                projectile.speed_vector,
+                wind.acceleration,
+                PHYSICS_TIME_STEP,
+            );
+            projectile.speed_vector = velocity;
+
+            //Sweep the whole step against tanks and walls, the earliest contact wins
+            let shooter: Option<Entity> = projectile.shooter;
+            let tank_impacts = tank_query
+                .iter()
+                .filter(|(tank, _)| Some(*tank) != shooter)
+                .filter_map(|(tank, t)| {
+                    tank_sweep_check(start, target, t.translation).map(|hit| (Some(tank), hit))
+                });
+            let wall_impact = tile_grid.sweep(start, target).map(|hit| (None, hit));
+            let impact: Option<(Option<Entity>, SweptHit)> = tank_impacts
+                .chain(wall_impact)
+                .min_by(|a, b| a.1.time.total_cmp(&b.1.time));
+
+            let Some((hit_tank, hit)) = impact else {
+                let tmp_transform_target: Vec3 = target.extend(p_transform.translation.z);
+                point_to_flight_direction(
+                    &mut p_transform,
+                    Vec2::new(projectile.speed_vector.x, projectile.speed_vector.y),
+                );
+                p_transform.translation = tmp_transform_target; //Move projectile by a single step
+                disarm_shooter_protection(&mut projectile, tmp_transform_target, &tank_query);
+
+                if map_bounds.is_in_kill_zone(target) {
+                    //Flew off the map - nothing left to hit
+                    commands.entity(entity).despawn_recursive();
+                    miss_events.send(ProjectileMissEvent { position: target });
+                    removed_projectiles.push(entity);
+                    continue;
+                }
+
+                if projectile.variant == ProjectileType::MIRV
+                    && previous_velocity.y > 0.0
+                    && velocity.y <= 0.0
+                {
+                    //Top of the arc - release the warheads, carrier is gone
+                    for warhead_velocity in mirv_warhead_velocities(velocity) {
+                        spawn_submunition(
+                            &mut commands,
+                            &texture,
+                            target,
+                            warhead_velocity,
+                            projectile.owner,
+                        );
+                        spawned_submunitions += 1;
+                    }
+                    explode_remove(&mut commands, entity, target, &sheet_query);
+                    removed_projectiles.push(entity);
+                }
+                continue;
+            };
+
+            let normal: Vec2 = impact_normal(hit.face, projectile.speed_vector);
+            match projectile.variant {
+                ProjectileType::AP
+                    if projectile.ricochets < AP_MAX_RICOCHETS
+                        && is_ricochet(projectile.speed_vector, normal) =>
+                {
+                    //Glancing hit - bounce off the surface and keep flying, no damage
+                    projectile.speed_vector = ricochet_velocity(projectile.speed_vector, normal);
+                    projectile.ricochets += 1;
+                    p_transform.translation.x = hit.position.x + normal.x * RICOCHET_SURFACE_GAP;
+                    p_transform.translation.y = hit.position.y + normal.y * RICOCHET_SURFACE_GAP;
+                    point_to_flight_direction(&mut p_transform, projectile.speed_vector);
+                    continue;
+                }
+                ProjectileType::AP => {
+                    //Penetration - only the tank that was hit takes damage
+                    if let Some(tank) = hit_tank {
+                        damage_events.send(DamageEvent {
+                            target: tank,
+                            amount: AP_DIRECT_HIT_DAMAGE,
+                        });
+                    }
+                }
+                ProjectileType::CLUSTER => {
+                    send_splash_damage(hit.point, &tank_query, &mut damage_events);
+                    let release_point: Vec2 = hit.point + normal * CLUSTER_RELEASE_OFFSET;
+                    for bomblet_velocity in cluster_bomblet_velocities(normal) {
+                        spawn_submunition(
+                            &mut commands,
+                            &texture,
+                            release_point,
+                            bomblet_velocity,
+                            projectile.owner,
+                        );
+                        spawned_submunitions += 1;
+                    }
+                }
+                ProjectileType::HE | ProjectileType::AIRBURST | ProjectileType::MIRV => {
+                    send_splash_damage(hit.point, &tank_query, &mut damage_events);
+                }
+            }
+
+            crater_events.send(CraterEvent {
+                center: hit.point,
+                radius: crater_radius(projectile.variant),
+            });
+            impact_events.send(ShellImpactEvent {
+                owner: projectile.owner,
+                position: hit.point,
+            });
+            stop_movement(&mut projectile);
+            explode_remove(&mut commands, entity, hit.point, &sheet_query);
+            removed_projectiles.push(entity);
+        }
+    }
+
+    //Last shell is gone and nothing new was released - allow firing again
+    if !removed_projectiles.is_empty()
+        && removed_projectiles.len() == live_projectiles
+        && spawned_submunitions == 0
     {
-        point_to_flight_direction(
-            &mut p_transform,
-            Vec2::new(projectile.speed_vector.x, projectile.speed_vector.y),
-        );
-        p_transform.translation = tmp_transform_target; //Move projectile in a single tick
-    } else {
-        //Collided with ground or player
-        stop_movement(&mut projectile);
-        explode_remove(commands, entity, p_transform.into_inner(), sheet_query);
+        projectile_state_query
+            .set(ProjectilePresent::NA)
+            .expect("Cannot change projectile state :/");
     }
 }
 
 fn explode_remove(
-    mut commands: Commands,
+    commands: &mut Commands,
     projectile_entity: Entity,
-    projectile_transform: &Transform,
-    sheet_query: Res<ExplosionTexturesSheet>,
+    impact_point: Vec2,
+    sheet_query: &ExplosionTexturesSheet,
 ) {
     //1. remove projectile entity
     commands.entity(projectile_entity).despawn_recursive();
 
-    //2. spawn explosion animation on its place
+    //2. spawn explosion animation on the hit surface
     spawn_animation(
-        &mut commands,
-        Vec3::new(
-            projectile_transform.translation.x,
-            projectile_transform.translation.y,
-            Z_PROJECTILE,
-        ),
-        &sheet_query,
+        commands,
+        Vec3::new(impact_point.x, impact_point.y, Z_PROJECTILE),
+        sheet_query,
     );
 }
 
@@ -140,47 +337,137 @@ fn point_to_flight_direction(transform: &mut Transform, velocity: Vec2) {
     transform.rotation = Quat::from_rotation_z(angle);
 }
 
+fn crater_radius(variant: ProjectileType) -> f32 {
+    match variant {
+        ProjectileType::AP => AP_CRATER_RADIUS,
+        _ => HE_CRATER_RADIUS,
+    }
+}
+
+///Spawn a HE bomblet or warhead already in flight
+fn spawn_submunition(
+    commands: &mut Commands,
+    texture: &ProjectileTexture,
+    position: Vec2,
+    velocity: Vec2,
+    owner: Option<Entity>,
+) {
+    let submunition: Entity = produce_projectile(commands, texture, position);
+    commands.entity(submunition).insert(Projectile {
+        speed_vector: velocity,
+        variant: ProjectileType::HE,
+        lifetime: produce_lifetime_timer(),
+        shooter: None,
+        owner,
+        ricochets: 0,
+    });
+}
+
+///Spawn a shell leaving the barrel of given tank
+pub fn fire_shell(
+    commands: &mut Commands,
+    texture: &ProjectileTexture,
+    shot_events: &mut EventWriter<ShotFiredEvent>,
+    tank: Entity,
+    position: Vec2,
+    velocity: Vec2,
+    variant: ProjectileType,
+) -> Entity {
+    shot_events.send(ShotFiredEvent {
+        tank,
+        muzzle: position,
+    });
+    let shell: Entity = produce_projectile(commands, texture, position);
+    commands.entity(shell).insert(Projectile {
+        speed_vector: velocity,
+        variant,
+        lifetime: produce_lifetime_timer(),
+        shooter: Some(tank),
+        owner: Some(tank),
+        ricochets: 0,
+    });
+    shell
+}
+
+///Damage every tank around the HE impact point, less the further away it is
+fn send_splash_damage(
+    impact_point: Vec2,
+    tank_query: &Query<(Entity, &Transform), TankFilter>,
+    damage_events: &mut EventWriter<DamageEvent>,
+) {
+    for (tank, tank_transform) in tank_query.iter() {
+        let distance: f32 =
+            distance_to_tank_hitbox(impact_point, tank_transform.translation.truncate());
+        let amount: f32 = splash_damage(distance);
+        if amount > 0.0 {
+            damage_events.send(DamageEvent {
+                target: tank,
+                amount,
+            });
+        }
+    }
+}
+
+///Once the shell is clear of the tank that fired it, it can hit that tank too
+fn disarm_shooter_protection(
+    projectile: &mut Mut<Projectile>,
+    projectile_translation: Vec3,
+    tank_query: &Query<(Entity, &Transform), TankFilter>,
+) {
+    if let Some(shooter) = projectile.shooter {
+        let still_inside: bool = tank_query
+            .get(shooter)
+            .map(|(_, t)| tank_hit_check(t.translation, projectile_translation))
+            .unwrap_or(false);
+        if !still_inside {
+            projectile.shooter = None;
+        }
+    }
+}
+
 fn stop_movement(projectile: &mut Mut<Projectile>) {
     projectile.speed_vector = Vec2::splat(0.0);
 }
 
 /// Fire new bullet - spawn entity and set its initial speed
-/// listen on spacebar press
+/// listen on spacebar release, power is charged while it is held
+#[allow(clippy::too_many_arguments)]
 fn fire(
     mut commands: Commands,
     texture: Res<ProjectileTexture>,
-    keyboard: Res<Input<KeyCode>>,
+    actions: Res<Input<InputAction>>,
     mut projectile_state_query: ResMut<State<ProjectilePresent>>,
-    aim_query: Query<&mut PlayerAim, With<Player>>,
-    player_query: Query<&Transform, With<Player>>,
+    mut aim_query: Query<&mut PlayerAim, With<Player>>,
+    mut player_query: Query<(Entity, &Transform, &mut WeaponInventory), With<Player>>,
+    match_mode: Res<MatchMode>,
+    turn: Res<Turn>,
+    mut shot_events: EventWriter<ShotFiredEvent>,
 ) {
-    let player_transform = player_query.get_single().unwrap();
-    let aim = aim_query.get_single().unwrap();
+    let (player_entity, player_transform, mut inventory) = player_query.get_single_mut().unwrap();
+    let mut aim = aim_query.get_single_mut().unwrap();
     let angle: f32 = aim.angle; //Take value from aiming component
 
-    //y = sin(a)*v
-    //x = cos(a)*v
+    if !(actions.just_released(InputAction::Fire) && aim.charging) {
+        return;
+    }
+    aim.charging = false;
 
-    if keyboard.pressed(KeyCode::Space)
-        && projectile_state_query.current() == &ProjectilePresent::NA
+    if projectile_state_query.current() == &ProjectilePresent::NA
+        && match_mode.may_fire(&turn, TurnSide::PLAYER)
     {
-        let new_stationary_projectile: Entity = produce_projectile(
+        let Some(variant) = inventory.take_selected() else {
+            return; //Out of ammo
+        };
+        fire_shell(
             &mut commands,
             &texture,
-            // Vec2::new(-15.0 * MAP_TILE_SIZE, -8.0 * MAP_TILE_SIZE),
-            Vec2::new(
-                player_transform.translation.x,
-                player_transform.translation.y,
-            ),
+            &mut shot_events,
+            player_entity,
+            barrel_tip(player_transform, angle),
+            produce_initial_speed_vector(angle, aim.power),
+            variant,
         );
 
-        commands
-            .entity(new_stationary_projectile)
-            .insert(Projectile {
-                speed_vector: produce_initial_speed_vector(angle),
-                variant: ProjectileType::HE,
-            });
-
         //Change state - block multiple entities at once
         projectile_state_query
             .set(ProjectilePresent::PRESENT)
@@ -188,18 +475,17 @@ fn fire(
     }
 }
 
-fn produce_initial_speed_vector(angle: f32) -> Vec2 {
-    let radian_angle: f32 = f32::to_radians(angle);
-    Vec2::new(f32::cos(radian_angle), f32::sin(radian_angle)).mul(PROJECTILE_MUZZLE_VELOCITY)
+fn produce_lifetime_timer() -> Timer {
+    Timer::from_seconds(PROJECTILE_MAX_FLIGHT_TIME, TimerMode::Once)
 }
 
 fn produce_projectile(
     commands: &mut Commands,
-    texture: &Res<ProjectileTexture>,
+    texture: &ProjectileTexture,
     initial_position: Vec2,
 ) -> Entity {
     let texture = texture.0.clone();
-    return commands
+    commands
         .spawn(SpriteBundle {
             texture,
             transform: Transform {
@@ -213,7 +499,11 @@ fn produce_projectile(
         .insert(Projectile {
             speed_vector: Vec2::splat(0.0),
             variant: ProjectileType::HE,
+            lifetime: produce_lifetime_timer(),
+            shooter: None,
+            owner: None,
+            ricochets: 0,
         })
         .insert(Name::new("Projectile"))
-        .id();
+        .id()
 }
@@ -0,0 +1,196 @@
+use bevy::app::App;
+use bevy::prelude::{
+    Component, EulerRot, IntoSystemDescriptor, Plugin, Quat, Query, Reflect, Res, SystemSet, Time,
+    Transform, Vec3,
+};
+
+use crate::configuration_properties::{
+    MAP_GRAVITY, MAP_TILE_SIZE, PHYSICS_TIME_STEP, TANK_MAX_STEP_HEIGHT, TANK_MAX_TILT,
+    TANK_TILT_SPEED, TANK_TRACK_OFFSET,
+};
+use crate::plugin::physics::PhysicsClock;
+use crate::state::MainGameState;
+use crate::utilsystems::tile_grid::TileGrid;
+
+///Gravity, driving and terrain collision shared by every tank, player and enemies alike
+pub struct TankPhysicsPlugin;
+
+/// How far a freshly spawned tank is searched up out of the terrain, or down onto it
+const SETTLE_MAX_TILES: usize = 64;
+
+/// Steps per tile used when dropping a spawned tank onto the ground
+const SETTLE_STEPS_PER_TILE: usize = 4;
+
+/// Height resolution of climbing up ledges and following the ground down a slope
+const STEP_PROBE: f32 = MAP_TILE_SIZE / 4.0;
+
+#[derive(Component, Reflect)]
+pub struct TankBody {
+    pub(crate) speed: f32,
+    /// Requested drive direction, -1.0 left to 1.0 right, set by whoever controls the tank
+    pub(crate) drive: f32,
+    /// Physics steps this frame in which the tank moved sideways, by driving or climbing a ledge.
+    /// Falling does not count
+    pub(crate) driven_steps: u32,
+    pub(crate) ground_contact: bool,
+    vertical_velocity: f32,
+    /// Spawn position was already fitted to the terrain
+    settled: bool,
+}
+
+impl TankBody {
+    pub fn new(speed: f32) -> Self {
+        TankBody {
+            speed,
+            drive: 0.0,
+            driven_steps: 0,
+            ground_contact: false,
+            vertical_velocity: 0.0,
+            settled: false,
+        }
+    }
+}
+
+impl Plugin for TankPhysicsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_update(MainGameState::COMBAT)
+                .with_system(tank_physics)
+                .with_system(tilt_tanks.after(tank_physics)),
+        );
+    }
+}
+
+/// Move every tank - moving up and down is disabled. Simple gravity is simulated.
+/// Runs once per fixed physics step, so speed and falling do not depend on FPS
+pub(crate) fn tank_physics(
+    mut tank_query: Query<(&mut TankBody, &mut Transform)>,
+    tile_grid: Res<TileGrid>,
+    physics_clock: Res<PhysicsClock>,
+) {
+    for (mut body, mut transform) in tank_query.iter_mut() {
+        body.driven_steps = 0;
+
+        if !body.settled {
+            if tile_grid.is_empty() {
+                continue; //Map tiles are indexed at the end of the first frame
+            }
+            transform.translation = settle_on_ground(&tile_grid, transform.translation);
+            body.settled = true;
+        }
+
+        for _ in 0..physics_clock.steps {
+            body.vertical_velocity += -MAP_GRAVITY * PHYSICS_TIME_STEP;
+
+            let y_delta: f32 = body.vertical_velocity * PHYSICS_TIME_STEP;
+            let x_delta: f32 =
+                body.drive.clamp(-1.0, 1.0) * body.speed * MAP_TILE_SIZE * PHYSICS_TIME_STEP;
+
+            let target = transform.translation + Vec3::new(x_delta, 0.0, 0.0);
+            if !tile_grid.collides(target) {
+                if x_delta != 0.0 {
+                    body.driven_steps += 1;
+                    if body.ground_contact {
+                        //Stick to the ground when driving down a slope instead of hopping off each step
+                        transform.translation = snap_down(&tile_grid, target);
+                    } else {
+                        transform.translation = target;
+                    }
+                }
+            } else if body.ground_contact {
+                //Blocked - climb the ledge if it is low enough
+                if let Some(stepped) = step_up(&tile_grid, transform.translation, target) {
+                    body.driven_steps += 1;
+                    transform.translation = stepped;
+                }
+            }
+
+            let target = transform.translation + Vec3::new(0.0, y_delta, 0.0);
+            if !tile_grid.collides(target) {
+                body.ground_contact = false;
+                transform.translation = target;
+            } else {
+                body.ground_contact = true;
+                body.vertical_velocity = 0.0;
+            }
+        }
+    }
+}
+
+/// Turn the hull to the line between the ground under the left and the right track.
+/// Side with no ground close below is taken as level with the other one
+pub(crate) fn tilt_tanks(
+    time: Res<Time>,
+    mut tank_query: Query<(&TankBody, &mut Transform)>,
+    tile_grid: Res<TileGrid>,
+) {
+    let search_cells: i32 = (2.0 * TANK_MAX_STEP_HEIGHT / MAP_TILE_SIZE).ceil() as i32 + 1;
+    let max_turn: f32 = TANK_TILT_SPEED.to_radians() * time.delta_seconds();
+
+    for (body, mut transform) in tank_query.iter_mut() {
+        if !body.ground_contact {
+            continue; //Keep the hull as it was while airborne
+        }
+        let from_y: f32 = transform.translation.y + TANK_MAX_STEP_HEIGHT;
+        let ground = |x: f32| tile_grid.ground_height(x, from_y, search_cells);
+        let (left, right) = match (
+            ground(transform.translation.x - TANK_TRACK_OFFSET),
+            ground(transform.translation.x + TANK_TRACK_OFFSET),
+        ) {
+            (Some(left), Some(right)) => (left, right),
+            (Some(height), None) | (None, Some(height)) => (height, height),
+            (None, None) => continue,
+        };
+
+        let target: f32 = (right - left)
+            .atan2(2.0 * TANK_TRACK_OFFSET)
+            .clamp(-TANK_MAX_TILT.to_radians(), TANK_MAX_TILT.to_radians());
+        let (_, _, current) = transform.rotation.to_euler(EulerRot::XYZ);
+        let tilt: f32 = current + (target - current).clamp(-max_turn, max_turn);
+        transform.rotation = Quat::from_rotation_z(tilt);
+    }
+}
+
+/// Lowest lift, up to TANK_MAX_STEP_HEIGHT, that lets the tank onto `target`.
+/// Tank must have headroom to rise as well, it does not squeeze under overhangs
+fn step_up(tile_grid: &TileGrid, position: Vec3, target: Vec3) -> Option<Vec3> {
+    let probes: usize = (TANK_MAX_STEP_HEIGHT / STEP_PROBE).floor() as usize;
+    (1..=probes)
+        .map(|i| Vec3::new(0.0, i as f32 * STEP_PROBE, 0.0))
+        .take_while(|lift| !tile_grid.collides(position + *lift))
+        .map(|lift| target + lift)
+        .find(|stepped| !tile_grid.collides(*stepped))
+}
+
+/// Drop onto the ground if it is no more than TANK_MAX_STEP_HEIGHT below, otherwise let the tank fall
+fn snap_down(tile_grid: &TileGrid, position: Vec3) -> Vec3 {
+    let probes: usize = (TANK_MAX_STEP_HEIGHT / STEP_PROBE).floor() as usize;
+    for i in 1..=probes + 1 {
+        if tile_grid.collides(position - Vec3::new(0.0, i as f32 * STEP_PROBE, 0.0)) {
+            return position - Vec3::new(0.0, (i - 1) as f32 * STEP_PROBE, 0.0);
+        }
+    }
+    position
+}
+
+/// Lift a tank buried in terrain up to the surface, then drop one hanging in the air onto the ground.
+/// Tank with no ground below is left where it is, gravity takes it from there
+fn settle_on_ground(tile_grid: &TileGrid, translation: Vec3) -> Vec3 {
+    let mut position: Vec3 = translation;
+    for _ in 0..SETTLE_MAX_TILES {
+        if !tile_grid.collides(position) {
+            break;
+        }
+        position.y += MAP_TILE_SIZE;
+    }
+
+    let step: Vec3 = Vec3::new(0.0, MAP_TILE_SIZE / SETTLE_STEPS_PER_TILE as f32, 0.0);
+    let mut dropped: Vec3 = position;
+    for _ in 0..SETTLE_MAX_TILES * SETTLE_STEPS_PER_TILE {
+        if tile_grid.collides(dropped - step) {
+            return dropped;
+        }
+        dropped -= step;
+    }
+    position
+}
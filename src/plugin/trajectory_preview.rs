@@ -0,0 +1,111 @@
+use bevy::app::App;
+use bevy::math::{Vec2, Vec3};
+use bevy::prelude::{
+    Color, Commands, Component, Plugin, Query, Reflect, Res, SystemSet, Transform, Visibility,
+    With, Without,
+};
+
+use crate::configuration_properties::{
+    PHYSICS_TIME_STEP, PREVIEW_DOT_COUNT, PREVIEW_MAX_STEPS, PREVIEW_STEPS_PER_DOT, Z_PROJECTILE,
+};
+use crate::difficulty::Difficulty;
+use crate::plugin::ascii_sprite::{spawn_ascii_sprite, AsciiSheet};
+use crate::plugin::player::Player;
+use crate::plugin::wind::Wind;
+use crate::state::MainGameState;
+use crate::utilsystems::ballistics::{flight_step, produce_initial_speed_vector};
+use crate::utilsystems::player_aim::PlayerAim;
+use crate::utilsystems::tile_grid::TileGrid;
+use crate::utilsystems::turret::barrel_tip;
+
+///Dotted arc predicting where the next shell will fly, only for difficulties that allow it
+pub struct TrajectoryPreviewPlugin;
+
+#[derive(Component, Reflect)]
+pub struct TrajectoryDot;
+
+impl Plugin for TrajectoryPreviewPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(spawn_preview_dots).add_system_set(
+            SystemSet::on_update(MainGameState::COMBAT).with_system(update_trajectory_preview),
+        );
+    }
+}
+
+/// Dots are spawned once and then only moved or hidden
+fn spawn_preview_dots(mut commands: Commands, ascii: Res<AsciiSheet>) {
+    for i in 0..PREVIEW_DOT_COUNT {
+        let dot = spawn_ascii_sprite(
+            &mut commands,
+            &ascii,
+            '.' as usize,
+            Color::rgb(0.9, 0.9, 0.3),
+            Vec3::new(0.0, 0.0, Z_PROJECTILE - 1.0),
+            format!("preview_dot{}", i),
+        );
+        commands
+            .entity(dot)
+            .insert(TrajectoryDot)
+            .insert(Visibility { is_visible: false });
+    }
+}
+
+type DotFilter = (With<TrajectoryDot>, Without<Player>);
+
+fn update_trajectory_preview(
+    difficulty: Res<Difficulty>,
+    wind: Res<Wind>,
+    player_query: Query<(&Transform, &PlayerAim), With<Player>>,
+    tile_grid: Res<TileGrid>,
+    mut dot_query: Query<(&mut Transform, &mut Visibility), DotFilter>,
+) {
+    let path: Vec<Vec2> = match player_query.get_single() {
+        Ok((player_transform, aim)) if difficulty.trajectory_preview() => predict_path(
+            barrel_tip(player_transform, aim.angle),
+            produce_initial_speed_vector(aim.angle, aim.power),
+            wind.acceleration,
+            &tile_grid,
+        ),
+        _ => Vec::new(),
+    };
+
+    for (i, (mut dot_transform, mut dot_visibility)) in dot_query.iter_mut().enumerate() {
+        if let Some(point) = path.get(i) {
+            dot_transform.translation.x = point.x;
+            dot_transform.translation.y = point.y;
+            dot_visibility.is_visible = true;
+        } else if dot_visibility.is_visible {
+            dot_visibility.is_visible = false;
+        }
+    }
+}
+
+/// Simulate the flight step by step, keep every few positions until the first wall is hit.
+/// Walls are swept the same way as for flying shells
+fn predict_path(
+    start: Vec2,
+    initial_velocity: Vec2,
+    wind_acceleration: f32,
+    tile_grid: &TileGrid,
+) -> Vec<Vec2> {
+    let mut path: Vec<Vec2> = Vec::with_capacity(PREVIEW_DOT_COUNT);
+    let mut position: Vec2 = start;
+    let mut velocity: Vec2 = initial_velocity;
+
+    for step in 1..=PREVIEW_MAX_STEPS {
+        let start: Vec2 = position;
+        (position, velocity) =
+            flight_step(position, velocity, wind_acceleration, PHYSICS_TIME_STEP);
+
+        if tile_grid.sweep(start, position).is_some() {
+            break;
+        }
+        if step % PREVIEW_STEPS_PER_DOT == 0 {
+            path.push(position);
+            if path.len() == PREVIEW_DOT_COUNT {
+                break;
+            }
+        }
+    }
+    path
+}
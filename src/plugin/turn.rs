@@ -0,0 +1,193 @@
+use bevy::app::App;
+use bevy::prelude::{
+    Camera, Commands, Entity, Input, Plugin, Query, Reflect, Res, ResMut, Resource, State,
+    SystemSet, Time, Timer, With,
+};
+use bevy::time::TimerMode;
+
+use crate::configuration_properties::{HUD_LINE_TURN, TURN_TIME};
+use crate::plugin::ascii_sprite::AsciiSheet;
+use crate::plugin::controls::InputAction;
+use crate::state::{MainGameState, ProjectilePresent};
+use crate::utilsystems::hud_text::{refresh_hud_line, HudLine};
+
+///Optional turn-based match - tanks take turns, each with a countdown, limited movement and one shot
+pub struct TurnPlugin;
+
+/// How tanks share the battlefield, switched with T while no shell is in flight
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Resource, Debug, Default, Clone, Eq, PartialEq, Hash, Copy, Reflect)]
+pub enum MatchMode {
+    /// Free play - everyone moves and fires whenever they like
+    #[default]
+    REALTIME,
+    TURNBASED,
+}
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Copy, Reflect)]
+pub enum TurnSide {
+    PLAYER,
+    ENEMY,
+}
+
+/// Whose turn it is and what is left of it. Only used in the turn-based mode
+#[derive(Resource, Reflect)]
+pub struct Turn {
+    pub(crate) side: TurnSide,
+    countdown: Timer,
+    shot_fired: bool,
+    /// Counts turns since the match started, tells a new turn from an old one
+    pub(crate) number: u32,
+}
+
+impl Default for Turn {
+    fn default() -> Self {
+        Turn {
+            side: TurnSide::PLAYER,
+            countdown: Timer::from_seconds(TURN_TIME, TimerMode::Once),
+            shot_fired: false,
+            number: 0,
+        }
+    }
+}
+
+impl Turn {
+    /// Hand the turn over to the other side with a fresh countdown and movement
+    pub fn pass(&mut self) {
+        let side: TurnSide = match self.side {
+            TurnSide::PLAYER => TurnSide::ENEMY,
+            TurnSide::ENEMY => TurnSide::PLAYER,
+        };
+        *self = Turn {
+            side,
+            number: self.number + 1,
+            ..Turn::default()
+        };
+    }
+
+    /// Start over with the player turn
+    pub fn restart(&mut self) {
+        *self = Turn {
+            number: self.number + 1,
+            ..Turn::default()
+        };
+    }
+}
+
+impl MatchMode {
+    pub fn is_turn_based(&self) -> bool {
+        *self == MatchMode::TURNBASED
+    }
+
+    /// Whether given side may fire right now
+    pub fn may_fire(&self, turn: &Turn, side: TurnSide) -> bool {
+        match self {
+            MatchMode::REALTIME => true,
+            MatchMode::TURNBASED => turn.side == side && !turn.shot_fired,
+        }
+    }
+
+    /// Whether given side may drive right now - until its shot is taken in the turn-based mode
+    pub fn may_move(&self, turn: &Turn, side: TurnSide) -> bool {
+        self.may_fire(turn, side)
+    }
+}
+
+impl Plugin for TurnPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MatchMode>()
+            .init_resource::<Turn>()
+            .add_system_set(
+                SystemSet::on_update(MainGameState::COMBAT)
+                    .with_system(count_down_turn)
+                    .with_system(switch_match_mode)
+                    .with_system(update_turn_text),
+            )
+            .add_system_set(
+                SystemSet::on_enter(ProjectilePresent::PRESENT).with_system(mark_shot_fired),
+            )
+            // Shell lifecycle is over once the last one is gone
+            .add_system_set(
+                SystemSet::on_enter(ProjectilePresent::NA).with_system(end_turn_after_shot),
+            );
+    }
+}
+
+/// Run out the clock, unless the shot was already taken - then the shell decides when the turn ends
+fn count_down_turn(
+    time: Res<Time>,
+    match_mode: Res<MatchMode>,
+    projectile_state: Res<State<ProjectilePresent>>,
+    mut turn: ResMut<Turn>,
+) {
+    if !match_mode.is_turn_based()
+        || turn.shot_fired
+        || projectile_state.current() != &ProjectilePresent::NA
+    {
+        return;
+    }
+    turn.countdown.tick(time.delta());
+    if turn.countdown.finished() {
+        turn.pass();
+    }
+}
+
+fn mark_shot_fired(mut turn: ResMut<Turn>) {
+    turn.shot_fired = true;
+}
+
+fn end_turn_after_shot(match_mode: Res<MatchMode>, mut turn: ResMut<Turn>) {
+    if match_mode.is_turn_based() && turn.shot_fired {
+        turn.pass();
+    }
+}
+
+/// Toggle between free play and turn-based match, listen on the SwitchMatchMode action (T by default).
+/// Blocked while a shell is in flight, the new match always starts with the player turn
+fn switch_match_mode(
+    actions: Res<Input<InputAction>>,
+    projectile_state: Res<State<ProjectilePresent>>,
+    mut match_mode: ResMut<MatchMode>,
+    mut turn: ResMut<Turn>,
+) {
+    if !actions.just_pressed(InputAction::SwitchMatchMode)
+        || projectile_state.current() != &ProjectilePresent::NA
+    {
+        return;
+    }
+    *match_mode = match *match_mode {
+        MatchMode::REALTIME => MatchMode::TURNBASED,
+        MatchMode::TURNBASED => MatchMode::REALTIME,
+    };
+    turn.restart();
+}
+
+/// Respawn turn text when it changes, nothing is shown in free play
+fn update_turn_text(
+    mut commands: Commands,
+    ascii: Res<AsciiSheet>,
+    match_mode: Res<MatchMode>,
+    turn: Res<Turn>,
+    hud_query: Query<(Entity, &HudLine)>,
+    camera_query: Query<Entity, With<Camera>>,
+) {
+    let Ok(camera) = camera_query.get_single() else {
+        return;
+    };
+    let text: Option<String> = match_mode.is_turn_based().then(|| format_turn(&turn));
+    refresh_hud_line(
+        &mut commands,
+        &ascii,
+        camera,
+        &hud_query,
+        HUD_LINE_TURN,
+        text,
+    );
+}
+
+/// e.g. "TURN PLAYER 17s"
+fn format_turn(turn: &Turn) -> String {
+    let seconds_left: f32 = turn.countdown.duration().as_secs_f32() - turn.countdown.elapsed_secs();
+    format!("TURN {:?} {:>2}s", turn.side, seconds_left.ceil() as u32)
+}
@@ -0,0 +1,233 @@
+use bevy::app::App;
+use bevy::prelude::{
+    Camera, Commands, Entity, EventReader, Input, Plugin, Query, Reflect, Res, ResMut, Resource,
+    State, SystemSet, Time, Timer, Vec2, With,
+};
+use bevy::time::TimerMode;
+
+use crate::configuration_properties::{
+    HUD_LINE_WAVE, MAP_TILE_SIZE, TANK_HITBOX_SIZE, WAVE_BREAK_TIME, WAVE_COUNT, WAVE_SIZE_GROWTH,
+    WAVE_SPAWN_INTERVAL, WAVE_SPAWN_SPACING,
+};
+use crate::plugin::ascii_sprite::AsciiSheet;
+use crate::plugin::controls::InputAction;
+use crate::plugin::enemy::{spawn_enemy_tank, Enemy};
+use crate::plugin::health::TankDestroyedEvent;
+use crate::plugin::map::{MapBounds, SpawnPoints};
+use crate::plugin::player::Player;
+use crate::state::MainGameState;
+use crate::utilsystems::enemy_position_provider::generate_random_position;
+use crate::utilsystems::hud_text::{refresh_hud_line, HudLine};
+use crate::utilsystems::tank_sprite_resource::TankEnemyTexture;
+
+///Sends enemies in waves, each one bigger than the last.
+/// Enemies placed on the map at startup are the first wave
+pub struct WavePlugin;
+
+#[derive(Resource, Reflect)]
+pub struct Waves {
+    pub(crate) number: u32,
+    /// Enemies of the current wave still waiting to enter the map
+    pub(crate) pending: usize,
+    /// Endless waves, the match only ends when the player is destroyed
+    pub(crate) survival: bool,
+    spawn_timer: Timer,
+    break_timer: Timer,
+}
+
+impl Default for Waves {
+    fn default() -> Self {
+        Waves {
+            number: 1,
+            pending: 0,
+            survival: false,
+            spawn_timer: Timer::from_seconds(WAVE_SPAWN_INTERVAL, TimerMode::Repeating),
+            break_timer: Timer::from_seconds(WAVE_BREAK_TIME, TimerMode::Once),
+        }
+    }
+}
+
+impl Waves {
+    /// Enemies in given wave, the first one is whatever the map places
+    pub fn wave_size(wave: u32, first_wave_size: usize) -> usize {
+        first_wave_size + (wave.saturating_sub(1) as usize) * WAVE_SIZE_GROWTH
+    }
+}
+
+impl Plugin for WavePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Waves>()
+            .add_system_set(
+                SystemSet::on_update(MainGameState::COMBAT)
+                    .with_system(run_waves)
+                    .with_system(switch_survival_mode)
+                    .with_system(update_wave_text),
+            )
+            .add_system(report_survival);
+    }
+}
+
+/// Feed pending enemies in one by one, start the next wave once the map is clear
+#[allow(clippy::too_many_arguments)]
+fn run_waves(
+    mut commands: Commands,
+    time: Res<Time>,
+    texture: Res<TankEnemyTexture>,
+    spawn_points: Res<SpawnPoints>,
+    map_bounds: Res<MapBounds>,
+    mut waves: ResMut<Waves>,
+    mut game_state: ResMut<State<MainGameState>>,
+    enemy_query: Query<(), With<Enemy>>,
+) {
+    if waves.pending > 0 {
+        waves.spawn_timer.tick(time.delta());
+        if waves.spawn_timer.just_finished() {
+            let index: usize = waves.pending;
+            let name: String = format!("Enemy_W{}_{}", waves.number, index);
+            spawn_enemy_tank(
+                &mut commands,
+                &texture,
+                wave_spawn_position(&spawn_points, &map_bounds, index - 1),
+                name,
+            );
+            waves.pending -= 1;
+        }
+        return;
+    }
+
+    if !enemy_query.is_empty() {
+        return;
+    }
+
+    if !waves.survival && waves.number >= WAVE_COUNT {
+        eprintln!("All {} waves cleared - victory", WAVE_COUNT);
+        //Err when the player was destroyed this frame too and the menu is queued already
+        let _ = game_state.set(MainGameState::MENU);
+        return;
+    }
+
+    waves.break_timer.tick(time.delta());
+    if waves.break_timer.finished() {
+        waves.number += 1;
+        waves.pending = Waves::wave_size(waves.number, spawn_points.enemy.len().max(1));
+        waves.break_timer.reset();
+        waves.spawn_timer.reset();
+    }
+}
+
+/// Enemies take turns on the map spawn points, a random spot is used when there are none.
+/// Once every point is taken, further enemies line up left and right of them so tanks never overlap.
+/// tank_physics lifts them out of any terrain they land in
+fn wave_spawn_position(spawn_points: &SpawnPoints, map_bounds: &MapBounds, index: usize) -> Vec2 {
+    if spawn_points.enemy.is_empty() {
+        return Vec2::new(generate_random_position(), -17.9 * MAP_TILE_SIZE);
+    }
+    let point: Vec2 = spawn_points.enemy[index % spawn_points.enemy.len()];
+    let round: usize = index / spawn_points.enemy.len();
+
+    //0, +1, -1, +2, -2... spacings away from the spawn point
+    let distance: f32 = round.div_ceil(2) as f32 * WAVE_SPAWN_SPACING;
+    let offset: f32 = if round % 2 == 1 { distance } else { -distance };
+
+    let margin: f32 = MAP_TILE_SIZE + TANK_HITBOX_SIZE.x / 2.0; //Border wall and half a tank
+    let x: f32 = (point.x + offset)
+        .min(map_bounds.max.x - margin)
+        .max(map_bounds.min.x + margin);
+    Vec2::new(x, point.y)
+}
+
+/// Toggle survival mode, listen on the SwitchSurvival action (V by default)
+fn switch_survival_mode(actions: Res<Input<InputAction>>, mut waves: ResMut<Waves>) {
+    if actions.just_pressed(InputAction::SwitchSurvival) {
+        waves.survival = !waves.survival;
+    }
+}
+
+/// Respawn wave text when it changes
+fn update_wave_text(
+    mut commands: Commands,
+    ascii: Res<AsciiSheet>,
+    waves: Res<Waves>,
+    enemy_query: Query<(), With<Enemy>>,
+    hud_query: Query<(Entity, &HudLine)>,
+    camera_query: Query<Entity, With<Camera>>,
+) {
+    let Ok(camera) = camera_query.get_single() else {
+        return;
+    };
+    let text: String = format_wave(&waves, enemy_query.iter().count() + waves.pending);
+    refresh_hud_line(
+        &mut commands,
+        &ascii,
+        camera,
+        &hud_query,
+        HUD_LINE_WAVE,
+        Some(text),
+    );
+}
+
+/// e.g. "WAVE 2/3 ENEMIES 3" or "SURVIVAL WAVE 7 ENEMIES 4"
+fn format_wave(waves: &Waves, enemies_left: usize) -> String {
+    if waves.survival {
+        format!("SURVIVAL WAVE {} ENEMIES {}", waves.number, enemies_left)
+    } else {
+        format!(
+            "WAVE {}/{} ENEMIES {}",
+            waves.number, WAVE_COUNT, enemies_left
+        )
+    }
+}
+
+/// Survival run is over once the player is destroyed
+fn report_survival(
+    mut destroyed_events: EventReader<TankDestroyedEvent>,
+    waves: Res<Waves>,
+    player_query: Query<(), With<Player>>,
+) {
+    for destroyed in destroyed_events.iter() {
+        if waves.survival && player_query.contains(destroyed.tank) {
+            eprintln!("Survival over - made it to wave {}", waves.number);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bounds() -> MapBounds {
+        MapBounds {
+            min: Vec2::new(-700.0, -500.0),
+            max: Vec2::new(700.0, 10.0),
+        }
+    }
+
+    #[test]
+    fn enemies_sharing_one_spawn_point_do_not_overlap() {
+        let spawn_points: SpawnPoints = SpawnPoints {
+            player: Vec::new(),
+            enemy: vec![Vec2::new(100.0, -340.0)],
+        };
+        let positions: Vec<Vec2> = (0..5)
+            .map(|index| wave_spawn_position(&spawn_points, &bounds(), index))
+            .collect();
+
+        assert_eq!(positions[0], Vec2::new(100.0, -340.0));
+        for (i, a) in positions.iter().enumerate() {
+            for b in positions.iter().skip(i + 1) {
+                assert!((a.x - b.x).abs() >= TANK_HITBOX_SIZE.x, "{a} overlaps {b}");
+            }
+        }
+    }
+
+    #[test]
+    fn extra_enemies_stay_on_the_map() {
+        let spawn_points: SpawnPoints = SpawnPoints {
+            player: Vec::new(),
+            enemy: vec![Vec2::new(650.0, -340.0)],
+        };
+        let position: Vec2 = wave_spawn_position(&spawn_points, &bounds(), 1);
+
+        assert!(position.x + TANK_HITBOX_SIZE.x / 2.0 < bounds().max.x - MAP_TILE_SIZE + 0.1);
+    }
+}
@@ -0,0 +1,95 @@
+use bevy::app::App;
+use bevy::prelude::{
+    Camera, Commands, Component, DespawnRecursiveExt, Entity, Plugin, Query, Reflect, Res, ResMut,
+    Resource, SystemSet, With,
+};
+use rand::Rng;
+
+use crate::configuration_properties::{HUD_LINE_WIND, WIND_MAX_ACCELERATION};
+use crate::plugin::ascii_sprite::AsciiSheet;
+use crate::state::ProjectilePresent;
+use crate::utilsystems::hud_text::spawn_hud_text;
+
+///Horizontal wind pushing projectiles, changes after every shot
+pub struct WindPlugin;
+
+#[derive(Resource, Reflect)]
+pub struct Wind {
+    /// Horizontal acceleration applied to shells, positive blows to the right
+    pub(crate) acceleration: f32,
+    /// Wind is rolled from -max_acceleration..max_acceleration
+    pub(crate) max_acceleration: f32,
+}
+
+#[derive(Component, Reflect)]
+pub struct WindText;
+
+impl Default for Wind {
+    fn default() -> Self {
+        Wind {
+            acceleration: 0.0,
+            max_acceleration: WIND_MAX_ACCELERATION,
+        }
+    }
+}
+
+impl Plugin for WindPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Wind>()
+            // Entering NA happens at startup and every time the last shell is gone
+            .add_system_set(SystemSet::on_enter(ProjectilePresent::NA).with_system(change_wind))
+            .add_system(update_wind_text);
+    }
+}
+
+fn change_wind(mut wind: ResMut<Wind>) {
+    let max: f32 = wind.max_acceleration.abs();
+    wind.acceleration = if max > 0.0 {
+        rand::thread_rng().gen_range(-max..=max)
+    } else {
+        0.0
+    };
+}
+
+/// Respawn wind text whenever the wind changes
+fn update_wind_text(
+    mut commands: Commands,
+    wind: Res<Wind>,
+    ascii: Option<Res<AsciiSheet>>,
+    text_query: Query<Entity, With<WindText>>,
+    camera_query: Query<Entity, With<Camera>>,
+) {
+    let (Some(ascii), Ok(camera)) = (ascii, camera_query.get_single()) else {
+        return;
+    };
+    if !wind.is_changed() && !text_query.is_empty() {
+        return;
+    }
+
+    for old_text in text_query.iter() {
+        commands.entity(old_text).despawn_recursive();
+    }
+
+    let text_entity = spawn_hud_text(
+        &mut commands,
+        &ascii,
+        camera,
+        &format_wind(&wind),
+        HUD_LINE_WIND,
+    );
+    commands.entity(text_entity).insert(WindText);
+}
+
+/// e.g. "WIND >>  45%" - arrows show direction and roughly the strength
+fn format_wind(wind: &Wind) -> String {
+    let ratio: f32 = if wind.max_acceleration != 0.0 {
+        (wind.acceleration / wind.max_acceleration).abs().min(1.0)
+    } else {
+        0.0
+    };
+    let arrow: char = if wind.acceleration < 0.0 { '<' } else { '>' };
+    let arrow_count: usize = (ratio * 3.0).ceil() as usize;
+    let arrows: String = arrow.to_string().repeat(arrow_count);
+
+    format!("WIND {:<3} {:>3}%", arrows, (ratio * 100.0).round() as u32)
+}
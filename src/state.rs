@@ -1,11 +1,13 @@
 use bevy::prelude::Reflect;
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Copy, Reflect)]
 pub enum MainGameState {
     MENU,
     COMBAT,
 }
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Copy, Reflect)]
 pub enum ProjectilePresent {
     PRESENT,
@@ -0,0 +1,64 @@
+use bevy::prelude::*;
+
+use crate::configuration_properties::{TANK_MAX_ELEVATION, TANK_MIN_ELEVATION};
+
+/// Elevation range of a tank's gun in degrees above the horizon, the same on both sides.
+/// With the maximum at 90 the barrel swings over the top from one side to the other
+#[derive(Component, Reflect)]
+pub struct AimLimits {
+    pub(crate) min_elevation: f32,
+    pub(crate) max_elevation: f32,
+}
+
+impl Default for AimLimits {
+    fn default() -> Self {
+        AimLimits {
+            min_elevation: TANK_MIN_ELEVATION,
+            max_elevation: TANK_MAX_ELEVATION,
+        }
+    }
+}
+
+impl AimLimits {
+    /// Wraps `angle` and brings it back into the allowed elevation on the side it points to
+    pub fn clamp(&self, angle: f32) -> f32 {
+        let angle: f32 = wrap_angle(angle);
+        let facing_left: bool = angle_facing_left(angle);
+        let elevation: f32 = elevation(angle).clamp(
+            self.min_elevation,
+            self.max_elevation.max(self.min_elevation),
+        );
+
+        if facing_left {
+            wrap_angle(180.0 - elevation)
+        } else {
+            elevation
+        }
+    }
+}
+
+/// Same direction expressed in -180..=180 degrees, so the angle never winds up
+pub fn wrap_angle(angle: f32) -> f32 {
+    let wrapped: f32 = (angle + 180.0).rem_euclid(360.0) - 180.0;
+    if wrapped == -180.0 {
+        180.0
+    } else {
+        wrapped
+    }
+}
+
+/// Degrees above the horizon on the side the angle points to, negative below it
+pub fn elevation(angle: f32) -> f32 {
+    let angle: f32 = wrap_angle(angle);
+    if !angle_facing_left(angle) {
+        angle
+    } else if angle > 0.0 {
+        180.0 - angle
+    } else {
+        -180.0 - angle
+    }
+}
+
+pub fn angle_facing_left(angle: f32) -> bool {
+    wrap_angle(angle).abs() > 90.0
+}
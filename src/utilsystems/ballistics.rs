@@ -0,0 +1,31 @@
+use std::ops::Mul;
+
+use bevy::math::Vec2;
+
+use crate::configuration_properties::{MAP_GRAVITY, PROJECTILE_AIR_DRAG};
+
+/// Initial shell velocity for given aim angle (degrees) and muzzle velocity
+pub fn produce_initial_speed_vector(angle: f32, power: f32) -> Vec2 {
+    //y = sin(a)*v
+    //x = cos(a)*v
+    let radian_angle: f32 = f32::to_radians(angle);
+    Vec2::new(f32::cos(radian_angle), f32::sin(radian_angle)).mul(power)
+}
+
+/// Advance a shell by a single physics step, returns new position and velocity.
+/// Flying projectiles and the trajectory preview both go through here, so they always agree
+pub fn flight_step(
+    position: Vec2,
+    velocity: Vec2,
+    wind_acceleration: f32,
+    delta_seconds: f32,
+) -> (Vec2, Vec2) {
+    let mut velocity: Vec2 = velocity;
+
+    //Calculate new vertical speed
+    velocity.y += -MAP_GRAVITY * delta_seconds; // acceleration * time = speed
+    velocity.x -= velocity.x * PROJECTILE_AIR_DRAG * delta_seconds; // Very simple air resistance approx. :)
+    velocity.x += wind_acceleration * delta_seconds; // Wind pushes shell sideways
+
+    (position + velocity * delta_seconds, velocity) // speed * time = distance
+}
@@ -0,0 +1,211 @@
+use bevy::math::Vec2;
+use bevy::prelude::Transform;
+
+use crate::configuration_properties::{
+    PHYSICS_TIME_STEP, PROJECTILE_MAX_FLIGHT_TIME, PROJECTILE_MAX_MUZZLE_VELOCITY,
+    PROJECTILE_MIN_MUZZLE_VELOCITY,
+};
+use crate::utilsystems::ballistics::{flight_step, produce_initial_speed_vector};
+use crate::utilsystems::tile_grid::TileGrid;
+use crate::utilsystems::turret::barrel_tip;
+
+/// Elevation range searched by the AI, mirrored when the target is on the left
+const ELEVATION_RANGE: (f32, f32) = (15.0, 75.0);
+
+const COARSE_ANGLE_STEP: f32 = 10.0;
+
+const COARSE_POWER_STEP: f32 = 40.0;
+
+const FINE_ANGLE_STEP: f32 = 1.0;
+
+const FINE_POWER_STEP: f32 = 2.0;
+
+/// Angle (degrees, the same as PlayerAim) and muzzle velocity for a shot
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FiringSolution {
+    pub angle: f32,
+    pub power: f32,
+}
+
+/// Search for the shot that passes closest to the target.
+/// Uses the same flight steps as flying shells and starts each one at the barrel tip, so wind, drag
+/// and the turret are accounted for.
+/// A coarse grid finds the area, then the steps are halved around the best shot - about 80 simulated flights
+pub fn solve_firing_solution(
+    tank_transform: &Transform,
+    target: Vec2,
+    wind_acceleration: f32,
+    tile_grid: &TileGrid,
+) -> FiringSolution {
+    let facing_left: bool = target.x < tank_transform.translation.x;
+    let miss = |elevation: f32, power: f32| -> f32 {
+        let angle: f32 = elevation_to_angle(elevation, facing_left);
+        let velocity: Vec2 = produce_initial_speed_vector(angle, power);
+        closest_approach(
+            barrel_tip(tank_transform, angle),
+            velocity,
+            wind_acceleration,
+            tile_grid,
+            target,
+        )
+    };
+
+    //Coarse grid over the whole range first, then refine around the best shot
+    let mut best: (f32, f32, f32) = (ELEVATION_RANGE.0, PROJECTILE_MIN_MUZZLE_VELOCITY, f32::MAX);
+    let mut elevation: f32 = ELEVATION_RANGE.0;
+    while elevation <= ELEVATION_RANGE.1 {
+        let mut power: f32 = PROJECTILE_MIN_MUZZLE_VELOCITY;
+        while power <= PROJECTILE_MAX_MUZZLE_VELOCITY {
+            let distance: f32 = miss(elevation, power);
+            if distance < best.2 {
+                best = (elevation, power, distance);
+            }
+            power += COARSE_POWER_STEP;
+        }
+        elevation += COARSE_ANGLE_STEP;
+    }
+
+    let mut angle_step: f32 = COARSE_ANGLE_STEP / 2.0;
+    let mut power_step: f32 = COARSE_POWER_STEP / 2.0;
+    while angle_step >= FINE_ANGLE_STEP / 2.0 || power_step >= FINE_POWER_STEP / 2.0 {
+        let (center_elevation, center_power) = (best.0, best.1);
+        for elevation_offset in [-angle_step, 0.0, angle_step] {
+            for power_offset in [-power_step, 0.0, power_step] {
+                let elevation: f32 = center_elevation + elevation_offset;
+                let power: f32 = (center_power + power_offset).clamp(
+                    PROJECTILE_MIN_MUZZLE_VELOCITY,
+                    PROJECTILE_MAX_MUZZLE_VELOCITY,
+                );
+                if elevation_offset == 0.0 && power_offset == 0.0 {
+                    continue; //Already measured
+                }
+                let distance: f32 = miss(elevation, power);
+                if distance < best.2 {
+                    best = (elevation, power, distance);
+                }
+            }
+        }
+        angle_step /= 2.0;
+        power_step /= 2.0;
+    }
+
+    FiringSolution {
+        angle: elevation_to_angle(best.0, facing_left),
+        power: best.1,
+    }
+}
+
+/// Elevation above the horizon turned into an aim angle facing left or right
+fn elevation_to_angle(elevation: f32, facing_left: bool) -> f32 {
+    if facing_left {
+        180.0 - elevation
+    } else {
+        elevation
+    }
+}
+
+/// Smallest distance between the simulated shell and the target, until the shell hits terrain
+fn closest_approach(
+    start: Vec2,
+    initial_velocity: Vec2,
+    wind_acceleration: f32,
+    tile_grid: &TileGrid,
+    target: Vec2,
+) -> f32 {
+    let max_steps: usize = (PROJECTILE_MAX_FLIGHT_TIME / PHYSICS_TIME_STEP) as usize;
+    let mut position: Vec2 = start;
+    let mut velocity: Vec2 = initial_velocity;
+    let mut closest: f32 = start.distance(target);
+
+    for _ in 0..max_steps {
+        let step_start: Vec2 = position;
+        (position, velocity) =
+            flight_step(position, velocity, wind_acceleration, PHYSICS_TIME_STEP);
+
+        if let Some(hit) = tile_grid.sweep(step_start, position) {
+            return closest.min(hit.point.distance(target));
+        }
+        closest = closest.min(position.distance(target));
+        if velocity.y < 0.0 && position.y < target.y - closest {
+            break; //Falling below the target and only getting further away
+        }
+        if (position.x - target.x) * velocity.x > closest * velocity.x.abs() {
+            break; //Flew past the target and only getting further away
+        }
+    }
+    closest
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::{Entity, IVec2, Transform, Vec2};
+
+    use super::{solve_firing_solution, FiringSolution};
+    use crate::configuration_properties::{
+        MAP_TILE_SIZE, PHYSICS_TIME_STEP, WIND_MAX_ACCELERATION,
+    };
+    use crate::utilsystems::ballistics::{flight_step, produce_initial_speed_vector};
+    use crate::utilsystems::tile_grid::TileGrid;
+    use crate::utilsystems::turret::barrel_tip;
+
+    /// Flat ground, top row of tiles at y = -18 cells
+    fn flat_ground() -> TileGrid {
+        let mut grid: TileGrid = TileGrid::default();
+        for x in -10..130 {
+            for y in -22..=-18 {
+                let tile: Entity = Entity::from_raw(((x + 10) * 10 - y) as u32);
+                grid.insert(tile, TileGrid::cell_to_world(IVec2::new(x, y)));
+            }
+        }
+        grid
+    }
+
+    /// Point on top of the ground in given column
+    fn ground_point(column: i32) -> Vec2 {
+        TileGrid::cell_to_world(IVec2::new(column, -18)) + Vec2::new(0.0, MAP_TILE_SIZE / 2.0)
+    }
+
+    /// Fire the solution from the barrel tip like enemy_fire does, returns where the shell hits the ground
+    fn impact(grid: &TileGrid, tank: &Transform, solution: FiringSolution, wind: f32) -> Vec2 {
+        let mut position: Vec2 = barrel_tip(tank, solution.angle);
+        let mut velocity: Vec2 = produce_initial_speed_vector(solution.angle, solution.power);
+        for _ in 0..10_000 {
+            let start: Vec2 = position;
+            (position, velocity) = flight_step(position, velocity, wind, PHYSICS_TIME_STEP);
+            if let Some(hit) = grid.sweep(start, position) {
+                return hit.point;
+            }
+        }
+        panic!("Shell never landed");
+    }
+
+    fn assert_lands_within_a_tile(tank_column: i32, target_column: i32, wind: f32) {
+        let grid: TileGrid = flat_ground();
+        let hull_center: Vec2 = ground_point(tank_column) + Vec2::new(0.0, MAP_TILE_SIZE);
+        let tank: Transform = Transform::from_translation(hull_center.extend(0.0));
+        let target: Vec2 = ground_point(target_column);
+
+        let solution: FiringSolution = solve_firing_solution(&tank, target, wind, &grid);
+        let landed: Vec2 = impact(&grid, &tank, solution, wind);
+
+        assert!(
+            landed.distance(target) <= MAP_TILE_SIZE,
+            "{solution:?} landed at {landed}, target {target}"
+        );
+    }
+
+    #[test]
+    fn solution_lands_on_target_to_the_right() {
+        assert_lands_within_a_tile(10, 60, 0.0);
+    }
+
+    #[test]
+    fn solution_lands_on_target_to_the_left() {
+        assert_lands_within_a_tile(100, 40, 0.0);
+    }
+
+    #[test]
+    fn solution_allows_for_wind() {
+        assert_lands_within_a_tile(10, 60, -WIND_MAX_ACCELERATION);
+    }
+}
@@ -0,0 +1,117 @@
+use bevy::prelude::*;
+
+use crate::configuration_properties::{FUEL_CAPACITY, HUD_LINE_FUEL, PHYSICS_TIME_STEP};
+use crate::difficulty::Difficulty;
+use crate::plugin::ascii_sprite::AsciiSheet;
+use crate::plugin::player::Player;
+use crate::plugin::tank_physics::TankBody;
+use crate::plugin::turn::{MatchMode, Turn, TurnSide};
+use crate::utilsystems::hud_text::{refresh_hud_line, HudLine};
+
+/// Seconds of driving left, burnt while the tank moves
+#[derive(Component, Reflect)]
+pub struct Fuel {
+    pub(crate) level: f32,
+    pub(crate) capacity: f32,
+}
+
+const FUEL_GAUGE_CELLS: usize = 10;
+
+impl Default for Fuel {
+    fn default() -> Self {
+        Fuel {
+            level: FUEL_CAPACITY,
+            capacity: FUEL_CAPACITY,
+        }
+    }
+}
+
+impl Fuel {
+    pub fn is_empty(&self) -> bool {
+        self.level <= 0.0
+    }
+
+    pub fn refill(&mut self) {
+        self.level = self.capacity;
+    }
+}
+
+/// Fuel only counts in the turn-based mode and on competitive difficulty, free driving otherwise
+pub fn fuel_limited(match_mode: &MatchMode, difficulty: &Difficulty) -> bool {
+    match_mode.is_turn_based() || *difficulty == Difficulty::COMPETITIVE
+}
+
+/// Drain fuel while the tank drives, one physics step worth for every step tank_physics moved it sideways.
+/// Counted in steps rather than frames so a tank drives equally far at any FPS, falling is free
+pub fn burn_fuel(
+    match_mode: Res<MatchMode>,
+    difficulty: Res<Difficulty>,
+    mut tank_query: Query<(&TankBody, &mut Fuel)>,
+) {
+    if !fuel_limited(&match_mode, &difficulty) {
+        return;
+    }
+    for (body, mut fuel) in tank_query.iter_mut() {
+        let burnt: f32 = body.driven_steps as f32 * PHYSICS_TIME_STEP;
+        fuel.level = (fuel.level - burnt).max(0.0);
+    }
+}
+
+/// Fill the player up at the start of each of its turns
+pub fn refuel_on_new_turn(
+    turn: Res<Turn>,
+    mut last_turn: Local<Option<u32>>,
+    mut fuel_query: Query<&mut Fuel, With<Player>>,
+) {
+    if *last_turn == Some(turn.number) {
+        return;
+    }
+    *last_turn = Some(turn.number);
+    if turn.side == TurnSide::PLAYER {
+        for mut fuel in fuel_query.iter_mut() {
+            fuel.refill();
+        }
+    }
+}
+
+/// Respawn fuel gauge text when it changes, nothing is shown while fuel is unlimited
+pub fn update_fuel_text(
+    mut commands: Commands,
+    ascii: Res<AsciiSheet>,
+    match_mode: Res<MatchMode>,
+    difficulty: Res<Difficulty>,
+    fuel_query: Query<&Fuel, With<Player>>,
+    hud_query: Query<(Entity, &HudLine)>,
+    camera_query: Query<Entity, With<Camera>>,
+) {
+    let (Ok(fuel), Ok(camera)) = (fuel_query.get_single(), camera_query.get_single()) else {
+        return;
+    };
+    let text: Option<String> =
+        fuel_limited(&match_mode, &difficulty).then(|| format_fuel_gauge(fuel));
+    refresh_hud_line(
+        &mut commands,
+        &ascii,
+        camera,
+        &hud_query,
+        HUD_LINE_FUEL,
+        text,
+    );
+}
+
+/// e.g. "FUEL [======----]", "FUEL [----------] EMPTY"
+fn format_fuel_gauge(fuel: &Fuel) -> String {
+    let ratio: f32 = if fuel.capacity > 0.0 {
+        (fuel.level / fuel.capacity).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let filled: usize = (ratio * FUEL_GAUGE_CELLS as f32).ceil() as usize;
+
+    format!(
+        "FUEL [{}{}]{}",
+        "=".repeat(filled),
+        "-".repeat(FUEL_GAUGE_CELLS - filled),
+        if fuel.is_empty() { " EMPTY" } else { "" }
+    )
+}
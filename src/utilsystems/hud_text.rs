@@ -0,0 +1,56 @@
+use bevy::prelude::{
+    BuildChildren, Commands, Component, DespawnRecursiveExt, Entity, Query, Reflect, Vec3,
+};
+
+use crate::configuration_properties::{
+    HUD_CAMERA_Z_OFFSET, MAP_TILE_SIZE, RESOLUTION_RATIO, WINDOW_HEIGHT,
+};
+use crate::plugin::ascii_sprite::{spawn_ascii_text, AsciiSheet};
+
+/// Text printed on a HUD line - (line, text). Kept to skip needless respawns
+#[derive(Component, Reflect)]
+pub struct HudLine(usize, String);
+
+/// Position of given HUD line relative to the camera, counting from the top left corner
+pub fn hud_line_position(line: usize) -> Vec3 {
+    Vec3::new(
+        RESOLUTION_RATIO * WINDOW_HEIGHT / -2.0 + MAP_TILE_SIZE,
+        WINDOW_HEIGHT / 2.0 - MAP_TILE_SIZE * (line as f32 + 1.0),
+        HUD_CAMERA_Z_OFFSET,
+    )
+}
+
+/// Spawn ascii text as a camera child, so it stays on screen whatever the camera does
+pub fn spawn_hud_text(
+    commands: &mut Commands,
+    ascii: &AsciiSheet,
+    camera: Entity,
+    text_to_print: &str,
+    line: usize,
+) -> Entity {
+    let text_entity = spawn_ascii_text(commands, ascii, text_to_print, hud_line_position(line));
+    commands.entity(camera).add_child(text_entity);
+    text_entity
+}
+
+/// Respawn text on `line` when it differs from the printed one, `None` clears the line
+pub fn refresh_hud_line(
+    commands: &mut Commands,
+    ascii: &AsciiSheet,
+    camera: Entity,
+    hud_query: &Query<(Entity, &HudLine)>,
+    line: usize,
+    text: Option<String>,
+) {
+    if let Some((text_entity, shown)) = hud_query.iter().find(|(_, shown)| shown.0 == line) {
+        if Some(&shown.1) == text.as_ref() {
+            return;
+        }
+        commands.entity(text_entity).despawn_recursive();
+    }
+
+    if let Some(text) = text {
+        let text_entity = spawn_hud_text(commands, ascii, camera, &text, line);
+        commands.entity(text_entity).insert(HudLine(line, text));
+    }
+}
@@ -1,5 +1,14 @@
+pub mod aim_limits;
+pub mod ballistics;
+pub mod enemy_aim;
 pub mod enemy_position_provider;
+pub mod fuel;
+pub mod hud_text;
 pub mod map_textures;
 pub mod player_aim;
+pub mod shell_effects;
 pub mod tank_sprite_resource;
+pub mod tile_grid;
+pub mod turret;
 pub mod wall_collision;
+pub mod weapon_inventory;
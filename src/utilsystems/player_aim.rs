@@ -1,13 +1,39 @@
-use crate::configuration_properties::{MAP_TILE_SIZE, Z_MAP, Z_PLAYER};
+use crate::configuration_properties::{
+    AIM_FINE_SPEED_FACTOR, AIM_SPEED, HUD_LINE_ANGLE, HUD_LINE_POWER, MAP_TILE_SIZE,
+    POWER_CHARGE_RATE, PROJECTILE_MAX_MUZZLE_VELOCITY, PROJECTILE_MIN_MUZZLE_VELOCITY,
+    PROJECTILE_MUZZLE_VELOCITY, Z_PLAYER,
+};
+use crate::plugin::ascii_sprite::AsciiSheet;
+use crate::plugin::controls::{ActionAxes, InputAction};
 use crate::plugin::player::Player;
+use crate::state::ProjectilePresent;
+use crate::utilsystems::aim_limits::{angle_facing_left, elevation, AimLimits};
+use crate::utilsystems::hud_text::{refresh_hud_line, HudLine};
 use bevy::prelude::*;
 use std::ops::Mul;
 
 #[derive(Component, Reflect)]
 pub struct PlayerAim {
+    /// Degrees from the world horizon pointing right, kept within -180..=180
     pub(crate) angle: f32,
+    /// Muzzle velocity of the next shot
+    pub(crate) power: f32,
+    /// Fire is held and power is building up
+    pub(crate) charging: bool,
 }
 
+impl Default for PlayerAim {
+    fn default() -> Self {
+        PlayerAim {
+            angle: 5.0,
+            power: PROJECTILE_MUZZLE_VELOCITY,
+            charging: false,
+        }
+    }
+}
+
+const POWER_METER_CELLS: usize = 10;
+
 pub const CH_RADIUS: f32 = 60.0;
 
 #[derive(Component, Reflect)]
@@ -31,16 +57,106 @@ pub fn spawn_aim_crosshair(mut commands: Commands, asset_server: Res<AssetServer
         .insert(Name::new("aim_ch"));
 }
 
+/// Aim speed follows how far the stick or trigger is pushed, keys always turn at full speed.
+/// Holding fine aim slows it down, the tank's elevation limits stop it
 pub fn update_aim_position(
-    keyboard: Res<Input<KeyCode>>,
+    time: Res<Time>,
+    actions: Res<Input<InputAction>>,
+    axes: Res<ActionAxes>,
+    mut aim_query: Query<(&mut PlayerAim, &AimLimits), With<Player>>,
+) {
+    let (mut aim_struct, limits) = aim_query.get_single_mut().unwrap();
+
+    let mut speed: f32 = AIM_SPEED * axes.aim;
+    if actions.pressed(InputAction::FineAim) {
+        speed *= AIM_FINE_SPEED_FACTOR;
+    }
+    aim_struct.angle = limits.clamp(aim_struct.angle + speed * time.delta_seconds());
+}
+
+/// Hold fire to build up power, starting from the minimum every time.
+/// Shot itself is fired on release
+pub fn charge_power(
+    actions: Res<Input<InputAction>>,
+    time: Res<Time>,
+    projectile_state: Res<State<ProjectilePresent>>,
     mut aim_query: Query<&mut PlayerAim, With<Player>>,
 ) {
     let mut aim_struct = aim_query.get_single_mut().unwrap();
 
-    if keyboard.pressed(KeyCode::W) {
-        aim_struct.angle += 0.07;
-    }
-    if keyboard.pressed(KeyCode::S) {
-        aim_struct.angle -= 0.07;
+    if actions.just_pressed(InputAction::Fire)
+        && projectile_state.current() == &ProjectilePresent::NA
+    {
+        aim_struct.power = PROJECTILE_MIN_MUZZLE_VELOCITY;
+        aim_struct.charging = true;
+    } else if aim_struct.charging && actions.pressed(InputAction::Fire) {
+        aim_struct.power = (aim_struct.power + POWER_CHARGE_RATE * time.delta_seconds())
+            .min(PROJECTILE_MAX_MUZZLE_VELOCITY);
     }
 }
+
+/// Respawn power meter text when the shown value changes
+pub fn update_power_meter(
+    mut commands: Commands,
+    ascii: Res<AsciiSheet>,
+    aim_query: Query<&PlayerAim, With<Player>>,
+    hud_query: Query<(Entity, &HudLine)>,
+    camera_query: Query<Entity, With<Camera>>,
+) {
+    let (Ok(aim_struct), Ok(camera)) = (aim_query.get_single(), camera_query.get_single()) else {
+        return;
+    };
+    let text: String = format_power_meter(aim_struct.power);
+    refresh_hud_line(
+        &mut commands,
+        &ascii,
+        camera,
+        &hud_query,
+        HUD_LINE_POWER,
+        Some(text),
+    );
+}
+
+/// Respawn angle text when the shown value changes
+pub fn update_angle_text(
+    mut commands: Commands,
+    ascii: Res<AsciiSheet>,
+    aim_query: Query<&PlayerAim, With<Player>>,
+    hud_query: Query<(Entity, &HudLine)>,
+    camera_query: Query<Entity, With<Camera>>,
+) {
+    let (Ok(aim_struct), Ok(camera)) = (aim_query.get_single(), camera_query.get_single()) else {
+        return;
+    };
+    let text: String = format_angle(aim_struct.angle);
+    refresh_hud_line(
+        &mut commands,
+        &ascii,
+        camera,
+        &hud_query,
+        HUD_LINE_ANGLE,
+        Some(text),
+    );
+}
+
+/// e.g. "ANGLE  45.0 >", elevation above the horizon and the side the gun points to
+fn format_angle(angle: f32) -> String {
+    let side: char = if angle_facing_left(angle) { '<' } else { '>' };
+    let shown: f32 = (elevation(angle) * 10.0).round() / 10.0 + 0.0; //No "-0.0"
+    format!("ANGLE {:>5.1} {}", shown, side)
+}
+
+/// e.g. "POWER [=====-----] 220"
+fn format_power_meter(power: f32) -> String {
+    let ratio: f32 = ((power - PROJECTILE_MIN_MUZZLE_VELOCITY)
+        / (PROJECTILE_MAX_MUZZLE_VELOCITY - PROJECTILE_MIN_MUZZLE_VELOCITY))
+        .clamp(0.0, 1.0);
+    let filled: usize = (ratio * POWER_METER_CELLS as f32).round() as usize;
+
+    format!(
+        "POWER [{}{}] {:>3}",
+        "=".repeat(filled),
+        "-".repeat(POWER_METER_CELLS - filled),
+        power.round() as u32
+    )
+}
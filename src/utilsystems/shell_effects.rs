@@ -0,0 +1,121 @@
+use bevy::math::Vec2;
+use bevy::sprite::collide_aabb::Collision;
+
+use crate::configuration_properties::{
+    AP_RICOCHET_ANGLE, AP_RICOCHET_SPEED_RETAINED, CLUSTER_BOMBLETS, CLUSTER_BOMBLET_SPEED,
+    CLUSTER_SPREAD_ANGLE, HE_SPLASH_DAMAGE, HE_SPLASH_RADIUS, MIRV_SPREAD_SPEED, MIRV_WARHEADS,
+    TANK_HITBOX_SIZE,
+};
+
+/// HE damage at given distance from the impact point, falls off linearly to zero at the splash edge
+pub fn splash_damage(distance: f32) -> f32 {
+    if distance >= HE_SPLASH_RADIUS {
+        return 0.0;
+    }
+    HE_SPLASH_DAMAGE * (1.0 - distance.max(0.0) / HE_SPLASH_RADIUS)
+}
+
+/// Distance from a point to the closest edge of the tank hitbox, zero if the point is inside
+pub fn distance_to_tank_hitbox(point: Vec2, tank_center: Vec2) -> f32 {
+    let outside: Vec2 = ((point - tank_center).abs() - TANK_HITBOX_SIZE / 2.0).max(Vec2::ZERO);
+    outside.length()
+}
+
+/// Outward normal of the face that was hit.
+/// `Inside` collisions have no side, so the face opposing the dominant flight direction is used
+pub fn impact_normal(collision: Collision, velocity: Vec2) -> Vec2 {
+    match collision {
+        Collision::Left => Vec2::new(-1.0, 0.0),
+        Collision::Right => Vec2::new(1.0, 0.0),
+        Collision::Top => Vec2::new(0.0, 1.0),
+        Collision::Bottom => Vec2::new(0.0, -1.0),
+        Collision::Inside => {
+            if velocity.x.abs() > velocity.y.abs() {
+                Vec2::new(-velocity.x.signum(), 0.0)
+            } else {
+                Vec2::new(0.0, -velocity.y.signum())
+            }
+        }
+    }
+}
+
+/// Angle in degrees between the incoming shell and the surface normal. 0 means head-on
+pub fn incidence_angle(velocity: Vec2, normal: Vec2) -> f32 {
+    if velocity == Vec2::ZERO {
+        return 0.0;
+    }
+    (-velocity).angle_between(normal).abs().to_degrees()
+}
+
+/// AP shells glance off surfaces hit at a shallow angle instead of penetrating
+pub fn is_ricochet(velocity: Vec2, normal: Vec2) -> bool {
+    incidence_angle(velocity, normal) >= AP_RICOCHET_ANGLE
+}
+
+/// Mirror the velocity off the surface, losing part of the speed
+pub fn ricochet_velocity(velocity: Vec2, normal: Vec2) -> Vec2 {
+    (velocity - 2.0 * velocity.dot(normal) * normal) * AP_RICOCHET_SPEED_RETAINED
+}
+
+/// Cluster bomblets fan out evenly around the normal of the surface that was hit
+pub fn cluster_bomblet_velocities(normal: Vec2) -> Vec<Vec2> {
+    let spread: f32 = CLUSTER_SPREAD_ANGLE.to_radians();
+    (0..CLUSTER_BOMBLETS)
+        .map(|i| {
+            let fraction: f32 = if CLUSTER_BOMBLETS > 1 {
+                i as f32 / (CLUSTER_BOMBLETS - 1) as f32
+            } else {
+                0.5
+            };
+            Vec2::from_angle(-spread + 2.0 * spread * fraction).rotate(normal)
+                * CLUSTER_BOMBLET_SPEED
+        })
+        .collect()
+}
+
+/// MIRV warheads keep the carrier velocity, spread evenly to the sides
+pub fn mirv_warhead_velocities(velocity: Vec2) -> Vec<Vec2> {
+    let middle: f32 = (MIRV_WARHEADS as f32 - 1.0) / 2.0;
+    (0..MIRV_WARHEADS)
+        .map(|i| velocity + Vec2::new((i as f32 - middle) * MIRV_SPREAD_SPEED, 0.0))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splash_damage_is_full_at_the_impact_point() {
+        assert_eq!(splash_damage(0.0), HE_SPLASH_DAMAGE);
+    }
+
+    #[test]
+    fn splash_damage_falls_off_linearly() {
+        assert_eq!(
+            splash_damage(HE_SPLASH_RADIUS / 2.0),
+            HE_SPLASH_DAMAGE / 2.0
+        );
+        assert!(splash_damage(HE_SPLASH_RADIUS / 4.0) > splash_damage(HE_SPLASH_RADIUS / 2.0));
+    }
+
+    #[test]
+    fn no_splash_damage_at_or_past_the_edge() {
+        assert_eq!(splash_damage(HE_SPLASH_RADIUS), 0.0);
+        assert_eq!(splash_damage(HE_SPLASH_RADIUS * 3.0), 0.0);
+    }
+
+    #[test]
+    fn hitbox_distance_is_zero_inside_the_tank() {
+        let center: Vec2 = Vec2::new(100.0, -50.0);
+
+        assert_eq!(distance_to_tank_hitbox(center, center), 0.0);
+        assert_eq!(
+            distance_to_tank_hitbox(
+                center + Vec2::new(TANK_HITBOX_SIZE.x / 2.0 + 10.0, 0.0),
+                center
+            ),
+            10.0
+        );
+    }
+}
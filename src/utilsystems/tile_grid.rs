@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+
+use bevy::prelude::{Entity, IVec2, Reflect, Resource, Vec2, Vec3};
+
+use crate::configuration_properties::{MAP_ORIGIN_X, MAP_TILE_SIZE};
+use crate::utilsystems::wall_collision::{wall_collision_check, wall_sweep_check, SweptHit};
+
+/// Tile colliders indexed by their map cell, so collision tests only look at nearby tiles
+/// instead of every tile on the map
+#[derive(Resource, Default, Reflect)]
+pub struct TileGrid {
+    #[reflect(ignore)]
+    cells: HashMap<IVec2, Entity>,
+    /// Reverse lookup, removed tiles are reported only by their entity
+    #[reflect(ignore)]
+    entity_cells: HashMap<Entity, IVec2>,
+}
+
+impl TileGrid {
+    /// Map cell containing given world position
+    pub fn world_to_cell(position: Vec2) -> IVec2 {
+        IVec2::new(
+            ((position.x - MAP_ORIGIN_X) / MAP_TILE_SIZE).round() as i32,
+            (position.y / MAP_TILE_SIZE).round() as i32,
+        )
+    }
+
+    /// World position of the cell centre, same place the tile sprite is spawned at
+    pub fn cell_to_world(cell: IVec2) -> Vec2 {
+        Vec2::new(
+            cell.x as f32 * MAP_TILE_SIZE + MAP_ORIGIN_X,
+            cell.y as f32 * MAP_TILE_SIZE,
+        )
+    }
+
+    /// Put the tile into the cell at `position`. A tile already in that cell is dropped,
+    /// a tile inserted again moves to the new cell
+    pub fn insert(&mut self, tile: Entity, position: Vec2) {
+        self.remove(tile);
+        let cell: IVec2 = TileGrid::world_to_cell(position);
+        if let Some(replaced) = self.cells.insert(cell, tile) {
+            self.entity_cells.remove(&replaced);
+        }
+        self.entity_cells.insert(tile, cell);
+    }
+
+    pub fn remove(&mut self, tile: Entity) {
+        if let Some(cell) = self.entity_cells.remove(&tile) {
+            if self.cells.get(&cell) == Some(&tile) {
+                self.cells.remove(&cell);
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn get(&self, cell: IVec2) -> Option<Entity> {
+        self.cells.get(&cell).copied()
+    }
+
+    /// Top of the highest tile in the column at `x`, searched down from `from_y` over `max_cells` cells
+    pub fn ground_height(&self, x: f32, from_y: f32, max_cells: i32) -> Option<f32> {
+        let start: IVec2 = TileGrid::world_to_cell(Vec2::new(x, from_y));
+        (0..=max_cells)
+            .map(|dy| start - IVec2::new(0, dy))
+            .find(|cell| self.cells.contains_key(cell))
+            .map(|cell| TileGrid::cell_to_world(cell).y + MAP_TILE_SIZE / 2.0)
+    }
+
+    /// Same result as running wall_collision_check against every tile, but only neighbours are tested
+    pub fn collides(&self, target: Vec3) -> bool {
+        let center: IVec2 = TileGrid::world_to_cell(target.truncate());
+        (-1..=1).any(|dx| {
+            (-1..=1).any(|dy| {
+                let cell: IVec2 = center + IVec2::new(dx, dy);
+                self.cells.contains_key(&cell)
+                    && wall_collision_check(TileGrid::cell_to_world(cell).extend(0.0), target)
+            })
+        })
+    }
+
+    /// Earliest wall hit along the segment, only cells around the segment are swept
+    pub fn sweep(&self, start: Vec2, end: Vec2) -> Option<SweptHit> {
+        let from: IVec2 = TileGrid::world_to_cell(start.min(end)) - IVec2::ONE;
+        let to: IVec2 = TileGrid::world_to_cell(start.max(end)) + IVec2::ONE;
+
+        let mut earliest: Option<SweptHit> = None;
+        for x in from.x..=to.x {
+            for y in from.y..=to.y {
+                let cell: IVec2 = IVec2::new(x, y);
+                if !self.cells.contains_key(&cell) {
+                    continue;
+                }
+                if let Some(hit) =
+                    wall_sweep_check(start, end, TileGrid::cell_to_world(cell).extend(0.0))
+                {
+                    if earliest.as_ref().is_none_or(|e| hit.time < e.time) {
+                        earliest = Some(hit);
+                    }
+                }
+            }
+        }
+        earliest
+    }
+
+    /// Tiles whose centre is within `radius` from `center`
+    pub fn tiles_within(&self, center: Vec2, radius: f32) -> Vec<Entity> {
+        let reach: IVec2 = IVec2::splat((radius / MAP_TILE_SIZE).ceil() as i32);
+        let from: IVec2 = TileGrid::world_to_cell(center) - reach;
+        let to: IVec2 = TileGrid::world_to_cell(center) + reach;
+
+        let mut tiles: Vec<Entity> = Vec::new();
+        for x in from.x..=to.x {
+            for y in from.y..=to.y {
+                let cell: IVec2 = IVec2::new(x, y);
+                if let Some(tile) = self.get(cell) {
+                    if TileGrid::cell_to_world(cell).distance(center) <= radius {
+                        tiles.push(tile);
+                    }
+                }
+            }
+        }
+        tiles
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::{Entity, IVec2, Vec2};
+
+    use super::TileGrid;
+    use crate::configuration_properties::MAP_TILE_SIZE;
+    use crate::utilsystems::wall_collision::{wall_collision_check, wall_sweep_check};
+
+    /// Both lookups point at each other and nothing else is left behind
+    fn assert_in_sync(grid: &TileGrid) {
+        assert_eq!(grid.cells.len(), grid.entity_cells.len());
+        for (cell, tile) in grid.cells.iter() {
+            assert_eq!(grid.entity_cells.get(tile), Some(cell));
+        }
+    }
+
+    /// Uneven terrain with a floating block and a hole, tile entities numbered from 0
+    fn uneven_ground() -> (TileGrid, Vec<Vec2>) {
+        let mut grid: TileGrid = TileGrid::default();
+        let mut tiles: Vec<Vec2> = Vec::new();
+        for x in 0..12 {
+            for y in -4..=(x % 4) - 2 {
+                if (x, y) != (5, -3) {
+                    tiles.push(TileGrid::cell_to_world(IVec2::new(x, y)));
+                }
+            }
+        }
+        tiles.push(TileGrid::cell_to_world(IVec2::new(7, 4)));
+        for (index, position) in tiles.iter().enumerate() {
+            grid.insert(Entity::from_raw(index as u32), *position);
+        }
+        (grid, tiles)
+    }
+
+    #[test]
+    fn insert_and_remove_keep_both_lookups_in_sync() {
+        let (mut grid, tiles) = uneven_ground();
+        assert_in_sync(&grid);
+
+        for index in (0..tiles.len()).step_by(3) {
+            grid.remove(Entity::from_raw(index as u32));
+        }
+        assert_in_sync(&grid);
+        assert_eq!(grid.get(TileGrid::world_to_cell(tiles[0])), None);
+
+        //Replace a tile in its cell, then move another one over
+        let replacement: Entity = Entity::from_raw(1000);
+        grid.insert(replacement, tiles[1]);
+        grid.insert(Entity::from_raw(2), tiles[4]);
+        assert_in_sync(&grid);
+        assert_eq!(
+            grid.get(TileGrid::world_to_cell(tiles[1])),
+            Some(replacement)
+        );
+        assert_eq!(grid.get(TileGrid::world_to_cell(tiles[2])), None);
+
+        //Removing the replaced tile must not take the new one with it
+        grid.remove(Entity::from_raw(1));
+        grid.remove(Entity::from_raw(4));
+        assert_in_sync(&grid);
+        assert_eq!(
+            grid.get(TileGrid::world_to_cell(tiles[1])),
+            Some(replacement)
+        );
+        assert_eq!(
+            grid.get(TileGrid::world_to_cell(tiles[4])),
+            Some(Entity::from_raw(2))
+        );
+
+        for index in 0..=1000 {
+            grid.remove(Entity::from_raw(index));
+        }
+        assert!(grid.is_empty());
+        assert_in_sync(&grid);
+    }
+
+    #[test]
+    fn collides_matches_testing_every_tile() {
+        let (grid, tiles) = uneven_ground();
+        let step: f32 = MAP_TILE_SIZE / 7.0;
+
+        for i in -20..110 {
+            for j in -40..60 {
+                let target: Vec2 = TileGrid::cell_to_world(IVec2::new(-1, -6))
+                    + Vec2::new(i as f32 * step, j as f32 * step);
+                let brute_force: bool = tiles
+                    .iter()
+                    .any(|tile| wall_collision_check(tile.extend(0.0), target.extend(0.0)));
+                assert_eq!(
+                    grid.collides(target.extend(0.0)),
+                    brute_force,
+                    "at {target}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn sweep_matches_testing_every_tile() {
+        let (grid, tiles) = uneven_ground();
+        let from: Vec2 = TileGrid::cell_to_world(IVec2::new(-2, 8));
+
+        for i in 0..60 {
+            let to: Vec2 = TileGrid::cell_to_world(IVec2::new(-2, -8))
+                + Vec2::new(i as f32 * MAP_TILE_SIZE / 3.0, 0.0);
+            let brute_force: Option<f32> = tiles
+                .iter()
+                .filter_map(|tile| wall_sweep_check(from, to, tile.extend(0.0)))
+                .map(|hit| hit.time)
+                .reduce(f32::min);
+            assert_eq!(
+                grid.sweep(from, to).map(|hit| hit.time),
+                brute_force,
+                "to {to}"
+            );
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use bevy::prelude::*;
+
+use crate::configuration_properties::{
+    MAP_TILE_SIZE, MUZZLE_FLASH_SCALE, TURRET_BARREL_LENGTH, TURRET_PIVOT_OFFSET,
+    TURRET_RECOIL_DISTANCE, TURRET_RECOIL_RECOVERY_SPEED, Z_PROJECTILE,
+};
+use crate::plugin::enemy::EnemyAi;
+use crate::plugin::explosion::{spawn_scaled_animation, ExplosionTexturesSheet};
+use crate::plugin::player::Player;
+use crate::plugin::projectile::ShotFiredEvent;
+use crate::utilsystems::player_aim::PlayerAim;
+
+/// Pivot of the barrel, a tank child rotated to the aim angle
+#[derive(Component, Reflect)]
+pub struct Turret;
+
+#[derive(Component, Reflect)]
+pub struct Barrel {
+    /// How far the barrel is currently pushed back into the turret
+    pub(crate) recoil: f32,
+}
+
+/// Barrel tip in the world for given tank aiming at `angle` degrees, measured from the world horizon.
+/// Turret pivot turns with the hull. Shells and the trajectory preview both start here
+pub fn barrel_tip(tank_transform: &Transform, angle: f32) -> Vec2 {
+    let radian_angle: f32 = angle.to_radians();
+    let pivot: Vec3 = tank_transform.rotation * TURRET_PIVOT_OFFSET.extend(0.0);
+    (tank_transform.translation + pivot).truncate()
+        + Vec2::new(radian_angle.cos(), radian_angle.sin()) * TURRET_BARREL_LENGTH
+}
+
+/// Attach a turret to the tank. Pivot undoes the tank sprite scale, so the barrel is sized in world units
+pub fn spawn_turret(commands: &mut Commands, tank: Entity, tank_scale: f32) {
+    let barrel = commands
+        .spawn(SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgb(0.25, 0.3, 0.2),
+                custom_size: Some(Vec2::new(TURRET_BARREL_LENGTH, MAP_TILE_SIZE * 0.3)),
+                ..default()
+            },
+            transform: Transform::from_xyz(TURRET_BARREL_LENGTH / 2.0, 0.0, -0.5),
+            ..default()
+        })
+        .insert(Barrel { recoil: 0.0 })
+        .insert(Name::new("Barrel"))
+        .id();
+
+    let turret = commands
+        .spawn(SpatialBundle {
+            transform: Transform {
+                translation: (TURRET_PIVOT_OFFSET / tank_scale).extend(0.0),
+                scale: Vec3::splat(1.0 / tank_scale),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(Turret)
+        .insert(Name::new("Turret"))
+        .add_child(barrel)
+        .id();
+
+    commands.entity(tank).add_child(turret);
+}
+
+type TurretFilter = (With<Turret>, Without<Player>, Without<EnemyAi>);
+
+/// Point turrets along the player aim and the last enemy shots. Aim is measured from the world horizon,
+/// so the hull tilt is taken back out of the turret rotation
+pub fn aim_turret(
+    aim_query: Query<(&PlayerAim, &Transform, &Children), With<Player>>,
+    enemy_query: Query<(&EnemyAi, &Transform, &Children)>,
+    mut turret_query: Query<&mut Transform, TurretFilter>,
+) {
+    let tanks = aim_query
+        .iter()
+        .map(|(aim, transform, children)| (aim.angle, transform, children))
+        .chain(
+            enemy_query
+                .iter()
+                .map(|(ai, transform, children)| (ai.aim_angle, transform, children)),
+        );
+
+    for (angle, hull_transform, children) in tanks {
+        for child in children.iter() {
+            if let Ok(mut turret_transform) = turret_query.get_mut(*child) {
+                turret_transform.rotation =
+                    hull_transform.rotation.inverse() * Quat::from_rotation_z(angle.to_radians());
+            }
+        }
+    }
+}
+
+/// Kick the barrel back and flash at the muzzle whenever a tank fires
+pub fn react_to_shots(
+    mut commands: Commands,
+    mut shot_events: EventReader<ShotFiredEvent>,
+    sheet: Res<ExplosionTexturesSheet>,
+    tank_query: Query<&Children>,
+    turret_query: Query<&Children, With<Turret>>,
+    mut barrel_query: Query<&mut Barrel>,
+) {
+    for shot in shot_events.iter() {
+        spawn_scaled_animation(
+            &mut commands,
+            shot.muzzle.extend(Z_PROJECTILE),
+            &sheet,
+            MUZZLE_FLASH_SCALE,
+        );
+
+        let Ok(tank_children) = tank_query.get(shot.tank) else {
+            continue;
+        };
+        for turret_children in tank_children
+            .iter()
+            .filter_map(|c| turret_query.get(*c).ok())
+        {
+            for barrel in turret_children.iter() {
+                if let Ok(mut barrel) = barrel_query.get_mut(*barrel) {
+                    barrel.recoil = TURRET_RECOIL_DISTANCE;
+                }
+            }
+        }
+    }
+}
+
+/// Slide the barrel back out after recoil
+pub fn recover_recoil(time: Res<Time>, mut barrel_query: Query<(&mut Barrel, &mut Transform)>) {
+    for (mut barrel, mut transform) in barrel_query.iter_mut() {
+        if barrel.recoil > 0.0 {
+            barrel.recoil =
+                (barrel.recoil - TURRET_RECOIL_RECOVERY_SPEED * time.delta_seconds()).max(0.0);
+        }
+        transform.translation.x = TURRET_BARREL_LENGTH / 2.0 - barrel.recoil;
+    }
+}
@@ -1,6 +1,18 @@
-use crate::configuration_properties::MAP_TILE_SIZE;
+use crate::configuration_properties::{MAP_TILE_SIZE, PROJECTILE_HITBOX_SIZE, TANK_HITBOX_SIZE};
 use bevy::prelude::{Vec2, Vec3};
-use bevy::sprite::collide_aabb::collide;
+use bevy::sprite::collide_aabb::{collide, Collision};
+
+/// Result of a swept collision test
+pub struct SweptHit {
+    /// Fraction of the tested segment travelled before the contact, 0..1
+    pub time: f32,
+    /// Centre of the moving box at the moment of contact
+    pub position: Vec2,
+    /// Contact point lying on the face that was hit
+    pub point: Vec2,
+    /// Side of the static box that was hit, `Inside` if the segment started overlapping it
+    pub face: Collision,
+}
 
 pub fn wall_collision_check(wall_translation: Vec3, target_player_pos: Vec3) -> bool {
     let collision = collide(
@@ -11,3 +23,172 @@ pub fn wall_collision_check(wall_translation: Vec3, target_player_pos: Vec3) ->
     );
     collision.is_some()
 }
+
+/// Direct hit test between a flying shell and a tank
+pub fn tank_hit_check(tank_translation: Vec3, projectile_translation: Vec3) -> bool {
+    let collision = collide(
+        projectile_translation,
+        Vec2::splat(PROJECTILE_HITBOX_SIZE),
+        tank_translation,
+        TANK_HITBOX_SIZE,
+    );
+    collision.is_some()
+}
+
+/// Swept hit test between a shell moving from `start` to `end` and a wall tile.
+/// The shell box is the same one tank_sweep_check uses
+pub fn wall_sweep_check(start: Vec2, end: Vec2, wall_translation: Vec3) -> Option<SweptHit> {
+    sweep_aabb(
+        start,
+        end,
+        Vec2::splat(PROJECTILE_HITBOX_SIZE),
+        wall_translation.truncate(),
+        Vec2::splat(MAP_TILE_SIZE),
+    )
+}
+
+/// Swept version of tank_hit_check for a shell moving from `start` to `end`
+pub fn tank_sweep_check(start: Vec2, end: Vec2, tank_translation: Vec3) -> Option<SweptHit> {
+    sweep_aabb(
+        start,
+        end,
+        Vec2::splat(PROJECTILE_HITBOX_SIZE),
+        tank_translation.truncate(),
+        TANK_HITBOX_SIZE,
+    )
+}
+
+/// Continuous collision of a box moving along a segment against a static box (slab method).
+/// Catches hits anywhere along the way, so fast objects cannot tunnel through thin walls
+pub fn sweep_aabb(
+    start: Vec2,
+    end: Vec2,
+    moving_size: Vec2,
+    box_center: Vec2,
+    box_size: Vec2,
+) -> Option<SweptHit> {
+    //Grow the static box by the moving one, then it is enough to trace a single point
+    let half_size: Vec2 = (box_size + moving_size) / 2.0;
+    let box_min: Vec2 = box_center - half_size;
+    let box_max: Vec2 = box_center + half_size;
+    let delta: Vec2 = end - start;
+
+    let mut entry_time: f32 = f32::NEG_INFINITY;
+    let mut exit_time: f32 = f32::INFINITY;
+    let mut entry_face: Collision = Collision::Inside;
+
+    for axis in 0..2 {
+        if delta[axis] == 0.0 {
+            if start[axis] <= box_min[axis] || start[axis] >= box_max[axis] {
+                return None; //Moving parallel to this slab and outside of it
+            }
+            continue;
+        }
+
+        let t_min: f32 = (box_min[axis] - start[axis]) / delta[axis];
+        let t_max: f32 = (box_max[axis] - start[axis]) / delta[axis];
+        let (near, far) = if t_min < t_max {
+            (t_min, t_max)
+        } else {
+            (t_max, t_min)
+        };
+
+        if near > entry_time {
+            entry_time = near;
+            entry_face = match (axis, delta[axis] > 0.0) {
+                (0, true) => Collision::Left,
+                (0, false) => Collision::Right,
+                (_, true) => Collision::Bottom,
+                (_, false) => Collision::Top,
+            };
+        }
+        exit_time = exit_time.min(far);
+    }
+
+    if entry_time >= exit_time || exit_time <= 0.0 || entry_time >= 1.0 {
+        return None;
+    }
+
+    if entry_time < 0.0 {
+        //Segment starts already overlapping the box
+        return Some(SweptHit {
+            time: 0.0,
+            position: start,
+            point: start,
+            face: Collision::Inside,
+        });
+    }
+
+    let position: Vec2 = start + delta * entry_time;
+    let point: Vec2 = match entry_face {
+        Collision::Left => Vec2::new(position.x + moving_size.x / 2.0, position.y),
+        Collision::Right => Vec2::new(position.x - moving_size.x / 2.0, position.y),
+        Collision::Bottom => Vec2::new(position.x, position.y + moving_size.y / 2.0),
+        _ => Vec2::new(position.x, position.y - moving_size.y / 2.0),
+    };
+
+    Some(SweptHit {
+        time: entry_time,
+        position,
+        point,
+        face: entry_face,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::Vec2;
+    use bevy::sprite::collide_aabb::Collision;
+
+    use super::{sweep_aabb, SweptHit};
+    use crate::configuration_properties::{MAP_TILE_SIZE, PROJECTILE_HITBOX_SIZE};
+
+    const SHELL: Vec2 = Vec2::splat(PROJECTILE_HITBOX_SIZE);
+    const TILE: Vec2 = Vec2::splat(MAP_TILE_SIZE);
+
+    #[test]
+    fn fast_shell_does_not_tunnel_through_a_tile() {
+        //Both ends are far outside the tile, a per-frame overlap test would miss it
+        let start: Vec2 = Vec2::new(-100.0, 5.0);
+        let end: Vec2 = Vec2::new(100.0, 5.0);
+
+        let hit: SweptHit = sweep_aabb(start, end, SHELL, Vec2::ZERO, TILE).expect("tunnelled");
+
+        assert_eq!(hit.face, Collision::Left);
+        assert_eq!(hit.point, Vec2::new(-MAP_TILE_SIZE / 2.0, 5.0));
+        assert_eq!(
+            hit.position.x,
+            -(MAP_TILE_SIZE + PROJECTILE_HITBOX_SIZE) / 2.0
+        );
+        assert!(hit.time > 0.0 && hit.time < 0.5);
+    }
+
+    #[test]
+    fn falling_shell_hits_the_top_face() {
+        let start: Vec2 = Vec2::new(3.0, 200.0);
+        let end: Vec2 = Vec2::new(-3.0, -200.0);
+
+        let hit: SweptHit = sweep_aabb(start, end, SHELL, Vec2::ZERO, TILE).expect("tunnelled");
+
+        assert_eq!(hit.face, Collision::Top);
+        assert!((hit.point.y - MAP_TILE_SIZE / 2.0).abs() < 1e-4);
+        assert!(hit.point.x.abs() <= MAP_TILE_SIZE / 2.0);
+    }
+
+    #[test]
+    fn shell_passing_beside_the_tile_misses() {
+        let clearance: f32 = (MAP_TILE_SIZE + PROJECTILE_HITBOX_SIZE) / 2.0 + 1.0;
+        let start: Vec2 = Vec2::new(-100.0, clearance);
+        let end: Vec2 = Vec2::new(100.0, clearance);
+
+        assert!(sweep_aabb(start, end, SHELL, Vec2::ZERO, TILE).is_none());
+    }
+
+    #[test]
+    fn shell_stopping_short_of_the_tile_misses() {
+        let start: Vec2 = Vec2::new(-100.0, 0.0);
+        let end: Vec2 = Vec2::new(-MAP_TILE_SIZE, 0.0);
+
+        assert!(sweep_aabb(start, end, SHELL, Vec2::ZERO, TILE).is_none());
+    }
+}
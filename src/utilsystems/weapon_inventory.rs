@@ -0,0 +1,137 @@
+use bevy::prelude::*;
+
+use crate::configuration_properties::{
+    AIRBURST_STARTING_AMMO, CLUSTER_STARTING_AMMO, HUD_LINE_WEAPON, MIRV_STARTING_AMMO,
+};
+use crate::plugin::ascii_sprite::AsciiSheet;
+use crate::plugin::controls::InputAction;
+use crate::plugin::player::Player;
+use crate::plugin::projectile::ProjectileType;
+use crate::utilsystems::hud_text::{refresh_hud_line, HudLine};
+
+#[derive(Reflect, FromReflect, Clone)]
+pub struct WeaponSlot {
+    pub(crate) variant: ProjectileType,
+    /// Shells left, None means unlimited
+    pub(crate) ammo: Option<u32>,
+}
+
+///Weapons carried by a tank and the one it will fire next
+#[derive(Component, Reflect)]
+pub struct WeaponInventory {
+    pub(crate) slots: Vec<WeaponSlot>,
+    pub(crate) selected: usize,
+}
+
+impl Default for WeaponInventory {
+    fn default() -> Self {
+        WeaponInventory {
+            slots: vec![
+                WeaponSlot {
+                    variant: ProjectileType::HE,
+                    ammo: None,
+                },
+                WeaponSlot {
+                    variant: ProjectileType::AP,
+                    ammo: None,
+                },
+                WeaponSlot {
+                    variant: ProjectileType::CLUSTER,
+                    ammo: Some(CLUSTER_STARTING_AMMO),
+                },
+                WeaponSlot {
+                    variant: ProjectileType::AIRBURST,
+                    ammo: Some(AIRBURST_STARTING_AMMO),
+                },
+                WeaponSlot {
+                    variant: ProjectileType::MIRV,
+                    ammo: Some(MIRV_STARTING_AMMO),
+                },
+            ],
+            selected: 0,
+        }
+    }
+}
+
+impl WeaponInventory {
+    pub fn selected_slot(&self) -> Option<&WeaponSlot> {
+        self.slots.get(self.selected)
+    }
+
+    /// Take one shell of the selected weapon, None if it ran out.
+    /// Empty weapon is swapped for the next one that still has ammo
+    pub fn take_selected(&mut self) -> Option<ProjectileType> {
+        let slot: &mut WeaponSlot = self.slots.get_mut(self.selected)?;
+        match slot.ammo {
+            Some(0) => return None,
+            Some(ammo) => slot.ammo = Some(ammo - 1),
+            None => {}
+        }
+        let variant: ProjectileType = slot.variant;
+
+        if slot.ammo == Some(0) {
+            self.select_next();
+        }
+        Some(variant)
+    }
+
+    /// Move selection to the next weapon with ammo left
+    pub fn select_next(&mut self) {
+        let slot_count: usize = self.slots.len();
+        for offset in 1..=slot_count {
+            let index: usize = (self.selected + offset) % slot_count;
+            if self.slots[index].ammo != Some(0) {
+                self.selected = index;
+                return;
+            }
+        }
+    }
+}
+
+/// Cycle through carried weapons, listen on the CycleWeapon action (E by default)
+pub fn cycle_weapon(
+    actions: Res<Input<InputAction>>,
+    mut inventory_query: Query<&mut WeaponInventory, With<Player>>,
+) {
+    if !actions.just_pressed(InputAction::CycleWeapon) {
+        return;
+    }
+    for mut inventory in inventory_query.iter_mut() {
+        inventory.select_next();
+    }
+}
+
+/// Respawn selected weapon text when it changes
+pub fn update_weapon_text(
+    mut commands: Commands,
+    ascii: Res<AsciiSheet>,
+    inventory_query: Query<&WeaponInventory, With<Player>>,
+    hud_query: Query<(Entity, &HudLine)>,
+    camera_query: Query<Entity, With<Camera>>,
+) {
+    let (Ok(inventory), Ok(camera)) = (inventory_query.get_single(), camera_query.get_single())
+    else {
+        return;
+    };
+    let text: String = format_weapon(inventory);
+    refresh_hud_line(
+        &mut commands,
+        &ascii,
+        camera,
+        &hud_query,
+        HUD_LINE_WEAPON,
+        Some(text),
+    );
+}
+
+/// e.g. "WEAPON CLUSTER x3", unlimited ammo is not printed
+fn format_weapon(inventory: &WeaponInventory) -> String {
+    match inventory.selected_slot() {
+        Some(WeaponSlot {
+            variant,
+            ammo: Some(ammo),
+        }) => format!("WEAPON {:?} x{}", variant, ammo),
+        Some(WeaponSlot { variant, .. }) => format!("WEAPON {:?}", variant),
+        None => "WEAPON -".to_string(),
+    }
+}